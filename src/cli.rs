@@ -0,0 +1,106 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use pong::{ControllerKind, Difficulty, Mode, RendererKind, Theme, ThemeName, WindowSettings, DEFAULT_SETTINGS_PATH};
+
+const DEFAULT_SCREENSHOT_SIZE: (u32, u32) = (800, 600);
+// The largest image side most GPUs support, which also keeps RGBA frame sizes within a u32
+const MAX_WINDOW_SIDE: u32 = 16384;
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Pong")]
+pub struct Cli {
+    /// Who controls each paddle: single, versus or demo
+    #[arg(long, default_value = "single")]
+    pub mode: Mode,
+
+    /// AI difficulty: easy, medium, hard or impossible
+    #[arg(long, default_value = "medium")]
+    pub difficulty: Difficulty,
+
+    /// Seed for serves and AI aim, random when omitted
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Arena, paddle, ball and rules settings as TOML, or JSON when the file ends in .json
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+
+    /// Points needed to win the match, overriding the game config
+    #[arg(long)]
+    pub points_to_win: Option<u32>,
+
+    /// Window size in pixels as WIDTHxHEIGHT
+    #[arg(long, value_parser = parse_window_size)]
+    pub window_size: Option<(u32, u32)>,
+
+    /// Start in borderless fullscreen
+    #[arg(long)]
+    pub fullscreen: bool,
+
+    /// Draw with vulkan, the CPU-only software renderer or in the terminal
+    #[arg(long, default_value = "vulkan")]
+    pub renderer: RendererKind,
+
+    /// Color theme: classic, neon, high-contrast or colorblind-safe. Overrides the settings file
+    #[arg(long)]
+    pub theme: Option<ThemeName>,
+
+    /// Play back a recorded replay instead of reading input. Overrides the match options
+    #[arg(long)]
+    pub replay: Option<PathBuf>,
+
+    /// Record the match to this replay file
+    #[arg(long)]
+    pub record: Option<PathBuf>,
+
+    /// Run the simulation without a window until the match is over
+    #[arg(long)]
+    pub headless: bool,
+
+    /// Stop a headless run after this many ticks
+    #[arg(long, requires = "headless")]
+    pub ticks: Option<u64>,
+
+    /// Render the last frame of a headless run offscreen to this PNG, sized by --window-size
+    #[arg(long, requires = "headless")]
+    pub screenshot: Option<PathBuf>,
+
+    /// Settings file with key bindings and display preferences
+    #[arg(long, default_value = DEFAULT_SETTINGS_PATH)]
+    pub settings: PathBuf,
+
+    /// Move the left paddle with the mouse cursor
+    #[arg(long)]
+    pub mouse: bool,
+}
+
+impl Cli {
+    pub fn controllers(&self) -> (ControllerKind, ControllerKind) {
+        self.mode.controllers(self.difficulty)
+    }
+
+    pub fn screenshot_size(&self) -> (u32, u32) {
+        self.window_size.unwrap_or(DEFAULT_SCREENSHOT_SIZE)
+    }
+
+    pub fn window_settings(&self, theme: Theme) -> WindowSettings {
+        WindowSettings { size: self.window_size, fullscreen: self.fullscreen, theme }
+    }
+}
+
+fn parse_window_size(value: &str) -> Result<(u32, u32), String> {
+    let (width, height) = value
+        .split_once('x')
+        .ok_or_else(|| format!("expected WIDTHxHEIGHT, got {}", value))?;
+    let width = parse_window_side(width).map_err(|message| format!("invalid width {}: {}", width, message))?;
+    let height = parse_window_side(height).map_err(|message| format!("invalid height {}: {}", height, message))?;
+    Ok((width, height))
+}
+
+fn parse_window_side(value: &str) -> Result<u32, String> {
+    match value.parse() {
+        Ok(side @ 1..=MAX_WINDOW_SIDE) => Ok(side),
+        _ => Err(format!("expected a number of pixels from 1 to {}", MAX_WINDOW_SIDE)),
+    }
+}
@@ -0,0 +1,235 @@
+use std::{error::Error, fmt, fs, io, path::Path};
+
+use serde::Deserialize;
+
+use crate::{Arena, BallSpeed, MatchRules, PaddleMotion};
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    Json(serde_json::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(error) => write!(f, "Failed to read game config: {}", error),
+            ConfigError::Toml(error) => write!(f, "Invalid game config: {}", error),
+            ConfigError::Json(error) => write!(f, "Invalid game config: {}", error),
+            ConfigError::Invalid(message) => write!(f, "Invalid game config: {}", message),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameConfig {
+    pub arena: Arena,
+    pub paddle: PaddleConfig,
+    pub ball: BallConfig,
+    pub rules: MatchRules,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PaddleConfig {
+    pub width: f32,
+    pub height: f32,
+    pub max_speed: f32,
+    pub acceleration: f32,
+    pub deceleration: f32,
+}
+
+impl Default for PaddleConfig {
+    fn default() -> Self {
+        let motion = PaddleMotion::default();
+        Self {
+            width: 2.0,
+            height: 10.0,
+            max_speed: motion.max_speed,
+            acceleration: motion.acceleration,
+            deceleration: motion.deceleration,
+        }
+    }
+}
+
+impl PaddleConfig {
+    pub fn motion(&self) -> PaddleMotion {
+        PaddleMotion { max_speed: self.max_speed, acceleration: self.acceleration, deceleration: self.deceleration }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BallConfig {
+    pub size: f32,
+    pub base_speed: f32,
+    pub acceleration: f32,
+    pub max_speed: f32,
+}
+
+impl Default for BallConfig {
+    fn default() -> Self {
+        let speed = BallSpeed::default();
+        Self { size: 2.0, base_speed: speed.base, acceleration: speed.acceleration, max_speed: speed.max }
+    }
+}
+
+impl BallConfig {
+    pub fn speed(&self) -> BallSpeed {
+        BallSpeed { base: self.base_speed, acceleration: self.acceleration, max: self.max_speed }
+    }
+}
+
+impl GameConfig {
+    // Reads JSON when the file ends in .json and TOML otherwise
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let contents = fs::read_to_string(path).map_err(ConfigError::Io)?;
+        let config: Self = if path.extension().is_some_and(|extension| extension == "json") {
+            serde_json::from_str(&contents).map_err(ConfigError::Json)?
+        } else {
+            toml::from_str(&contents).map_err(ConfigError::Toml)?
+        };
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let positive = [
+            ("arena.width", self.arena.width),
+            ("arena.height", self.arena.height),
+            ("paddle.width", self.paddle.width),
+            ("paddle.height", self.paddle.height),
+            ("paddle.max_speed", self.paddle.max_speed),
+            ("paddle.acceleration", self.paddle.acceleration),
+            ("paddle.deceleration", self.paddle.deceleration),
+            ("ball.size", self.ball.size),
+            ("ball.base_speed", self.ball.base_speed),
+        ];
+        for (name, value) in positive {
+            if !(value.is_finite() && value > 0.0) {
+                return Err(ConfigError::Invalid(format!("{} must be greater than 0, got {}", name, value)));
+            }
+        }
+
+        if self.paddle.height >= self.arena.height {
+            return Err(ConfigError::Invalid("paddle.height must be less than arena.height".to_string()));
+        }
+        if 2.0 * self.paddle.width + self.ball.size >= self.arena.width {
+            return Err(ConfigError::Invalid("both paddles and the ball must fit across the arena".to_string()));
+        }
+        if self.ball.size >= self.arena.height {
+            return Err(ConfigError::Invalid("ball.size must be less than arena.height".to_string()));
+        }
+        if !(self.ball.acceleration.is_finite() && self.ball.acceleration >= 0.0) {
+            return Err(ConfigError::Invalid(format!("ball.acceleration must not be negative, got {}", self.ball.acceleration)));
+        }
+        if !(self.ball.max_speed.is_finite() && self.ball.max_speed >= self.ball.base_speed) {
+            return Err(ConfigError::Invalid("ball.max_speed must be at least ball.base_speed".to_string()));
+        }
+        if self.rules.points_to_win == 0 {
+            return Err(ConfigError::Invalid("rules.points_to_win must be at least 1".to_string()));
+        }
+        if self.rules.win_by == 0 {
+            return Err(ConfigError::Invalid("rules.win_by must be at least 1".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    // Writes `contents` to a temporary file with the given name and loads it
+    fn load(name: &str, contents: &str) -> Result<GameConfig, ConfigError> {
+        let path: PathBuf = std::env::temp_dir().join(format!("pong-config-test-{}-{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        let config = GameConfig::load(&path);
+        fs::remove_file(&path).unwrap();
+        config
+    }
+
+    type Change = fn(&mut GameConfig);
+
+    fn invalid_message(config: GameConfig) -> String {
+        match config.validate() {
+            Err(ConfigError::Invalid(message)) => message,
+            result => panic!("Expected an invalid config, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn default_is_valid() {
+        assert!(GameConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let cases: [(Change, &str); 16] = [
+            (|config| config.arena.width = 0.0, "arena.width must be greater than 0, got 0"),
+            (|config| config.arena.height = -1.0, "arena.height must be greater than 0, got -1"),
+            (|config| config.paddle.width = f32::NAN, "paddle.width must be greater than 0, got NaN"),
+            (|config| config.paddle.height = 0.0, "paddle.height must be greater than 0, got 0"),
+            (|config| config.paddle.max_speed = 0.0, "paddle.max_speed must be greater than 0, got 0"),
+            (|config| config.paddle.acceleration = 0.0, "paddle.acceleration must be greater than 0, got 0"),
+            (|config| config.paddle.deceleration = 0.0, "paddle.deceleration must be greater than 0, got 0"),
+            (|config| config.ball.size = 0.0, "ball.size must be greater than 0, got 0"),
+            (|config| config.ball.base_speed = f32::INFINITY, "ball.base_speed must be greater than 0, got inf"),
+            (|config| config.paddle.height = config.arena.height, "paddle.height must be less than arena.height"),
+            (|config| config.paddle.width = config.arena.width / 2.0, "both paddles and the ball must fit across the arena"),
+            (|config| {
+                config.arena.width = 500.0;
+                config.ball.size = config.arena.height;
+            }, "ball.size must be less than arena.height"),
+            (|config| config.ball.acceleration = -0.1, "ball.acceleration must not be negative, got -0.1"),
+            (|config| config.ball.max_speed = config.ball.base_speed / 2.0, "ball.max_speed must be at least ball.base_speed"),
+            (|config| config.rules.points_to_win = 0, "rules.points_to_win must be at least 1"),
+            (|config| config.rules.win_by = 0, "rules.win_by must be at least 1"),
+        ];
+
+        for (change, message) in cases {
+            let mut config = GameConfig::default();
+            change(&mut config);
+            assert_eq!(invalid_message(config), message);
+        }
+    }
+
+    #[test]
+    fn reads_toml_or_json_by_extension() {
+        let toml = "[arena]\nwidth = 120.0\n\n[rules]\npoints_to_win = 5\n";
+        let json = r#"{ "arena": { "width": 120.0 }, "rules": { "points_to_win": 5 } }"#;
+
+        let mut expected = GameConfig::default();
+        expected.arena.width = 120.0;
+        expected.rules.points_to_win = 5;
+        assert_eq!(load("config.toml", toml).unwrap(), expected);
+        assert_eq!(load("config.json", json).unwrap(), expected);
+        // Files without an extension are TOML too
+        assert_eq!(load("config", toml).unwrap(), expected);
+
+        assert!(matches!(load("swapped.toml", json), Err(ConfigError::Toml(_))));
+        assert!(matches!(load("swapped.json", toml), Err(ConfigError::Json(_))));
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        assert!(matches!(load("unknown.toml", "[arena]\ndepth = 3.0\n"), Err(ConfigError::Toml(_))));
+        assert!(matches!(load("unknown.toml", "gravity = 1.0\n"), Err(ConfigError::Toml(_))));
+        assert!(matches!(load("unknown.json", r#"{ "ball": { "spin": 1.0 } }"#), Err(ConfigError::Json(_))));
+    }
+
+    #[test]
+    fn load_reports_errors() {
+        assert!(matches!(load("invalid.toml", "[rules]\nwin_by = 0\n"), Err(ConfigError::Invalid(_))));
+        assert!(matches!(GameConfig::load(Path::new("/nonexistent/pong.toml")), Err(ConfigError::Io(_))));
+    }
+}
@@ -1,2 +0,0 @@
-pub const MIN_POS: f32 = 0.0;
-pub const MAX_POS: f32 = 100.0;
\ No newline at end of file
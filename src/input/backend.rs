@@ -0,0 +1,156 @@
+use std::collections::{HashSet, VecDeque};
+
+use winit::event::VirtualKeyCode;
+
+use crate::{Action, KeyBindings, MoveCommand, Side, TickInput};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputEvent {
+    // The command a device currently gives one side's paddle, None once it is released
+    MOVE(Side, Option<MoveCommand>),
+    PRESSED(Action)
+}
+
+pub trait InputBackend {
+    // Returns the events that happened since the last poll
+    fn poll(&mut self) -> Vec<InputEvent>;
+}
+
+// Plays back queued events, one batch per poll
+#[derive(Default)]
+pub struct MockBackend {
+    polls: VecDeque<Vec<InputEvent>>
+}
+
+impl MockBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn queue(&mut self, events: Vec<InputEvent>) {
+        self.polls.push_back(events);
+    }
+}
+
+impl InputBackend for MockBackend {
+    fn poll(&mut self) -> Vec<InputEvent> {
+        self.polls.pop_front().unwrap_or_default()
+    }
+}
+
+// Stores the latest MOVE for each side in `device_commands` and applies a PRESSED action to
+// `tick_input`. Returns whether quitting was requested
+pub fn apply_event(event: InputEvent, device_commands: &mut [Option<MoveCommand>; 2], tick_input: &mut TickInput) -> bool {
+    match event {
+        InputEvent::MOVE(Side::LEFT, command) => device_commands[0] = command,
+        InputEvent::MOVE(Side::RIGHT, command) => device_commands[1] = command,
+        InputEvent::PRESSED(action) => return apply_action(action, tick_input)
+    }
+    false
+}
+
+// Returns whether quitting was requested
+pub fn apply_action(action: Action, tick_input: &mut TickInput) -> bool {
+    match action {
+        Action::SERVE => tick_input.serve = true,
+        Action::PAUSE => tick_input.toggle_pause = true,
+        Action::RESTART => tick_input.restart = true,
+        Action::QUIT => return true,
+        Action::MOVE(_, _) => ()
+    }
+    false
+}
+
+// Held keys take priority over other devices. In single player mode the right paddle's
+// inputs also move the left paddle
+pub fn paddle_commands(
+    key_bindings: &KeyBindings,
+    held_keys: &HashSet<VirtualKeyCode>,
+    device_commands: [Option<MoveCommand>; 2],
+    versus: bool
+) -> (Option<MoveCommand>, Option<MoveCommand>) {
+    let left = key_bindings.held_command(Side::LEFT, held_keys).or(device_commands[0]);
+    let right = key_bindings.held_command(Side::RIGHT, held_keys).or(device_commands[1]);
+
+    if versus {
+        (left, right)
+    } else {
+        (left.or(right), None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Polls the backend once and applies its events like the game loop does
+    fn poll_input(
+        backend: &mut MockBackend,
+        device_commands: &mut [Option<MoveCommand>; 2],
+        versus: bool
+    ) -> (TickInput, bool) {
+        let mut tick_input = TickInput::default();
+        let mut quit = false;
+        for event in backend.poll() {
+            quit |= apply_event(event, device_commands, &mut tick_input);
+        }
+        (tick_input.left_paddle, tick_input.right_paddle) =
+            paddle_commands(&KeyBindings::default(), &HashSet::new(), *device_commands, versus);
+        (tick_input, quit)
+    }
+
+    #[test]
+    fn events_map_to_tick_input() {
+        let mut backend = MockBackend::new();
+        backend.queue(vec![
+            InputEvent::MOVE(Side::LEFT, Some(MoveCommand::UP)),
+            InputEvent::MOVE(Side::RIGHT, Some(MoveCommand::AXIS(-0.5))),
+            InputEvent::PRESSED(Action::SERVE),
+        ]);
+        backend.queue(vec![InputEvent::PRESSED(Action::PAUSE), InputEvent::PRESSED(Action::RESTART)]);
+        backend.queue(vec![InputEvent::MOVE(Side::LEFT, None), InputEvent::PRESSED(Action::QUIT)]);
+        let mut device_commands = [None; 2];
+
+        let (input, quit) = poll_input(&mut backend, &mut device_commands, true);
+        assert_eq!(input, TickInput {
+            left_paddle: Some(MoveCommand::UP),
+            right_paddle: Some(MoveCommand::AXIS(-0.5)),
+            serve: true,
+            ..TickInput::default()
+        });
+        assert!(!quit);
+
+        // Commands stay held until the device releases them
+        let (input, quit) = poll_input(&mut backend, &mut device_commands, true);
+        assert_eq!(input, TickInput {
+            left_paddle: Some(MoveCommand::UP),
+            right_paddle: Some(MoveCommand::AXIS(-0.5)),
+            toggle_pause: true,
+            restart: true,
+            ..TickInput::default()
+        });
+        assert!(!quit);
+
+        let (input, quit) = poll_input(&mut backend, &mut device_commands, true);
+        assert_eq!(input.left_paddle, None);
+        assert!(quit);
+
+        // Nothing is left to play back
+        assert!(backend.poll().is_empty());
+    }
+
+    #[test]
+    fn single_player_moves_the_left_paddle_with_either_side() {
+        let mut backend = MockBackend::new();
+        backend.queue(vec![InputEvent::MOVE(Side::RIGHT, Some(MoveCommand::TARGET(3.0)))]);
+        backend.queue(vec![InputEvent::MOVE(Side::LEFT, Some(MoveCommand::DOWN))]);
+        let mut device_commands = [None; 2];
+
+        let (input, _) = poll_input(&mut backend, &mut device_commands, false);
+        assert_eq!((input.left_paddle, input.right_paddle), (Some(MoveCommand::TARGET(3.0)), None));
+
+        // The left side's own command takes priority
+        let (input, _) = poll_input(&mut backend, &mut device_commands, false);
+        assert_eq!((input.left_paddle, input.right_paddle), (Some(MoveCommand::DOWN), None));
+    }
+}
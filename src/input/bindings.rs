@@ -0,0 +1,93 @@
+use std::collections::HashSet;
+
+use serde::Deserialize;
+use winit::event::VirtualKeyCode;
+
+use crate::{MoveCommand, Side};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    MOVE(Side, MoveCommand),
+    SERVE,
+    PAUSE,
+    RESTART,
+    QUIT
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeyBindings {
+    pub left_up: Vec<VirtualKeyCode>,
+    pub left_down: Vec<VirtualKeyCode>,
+    pub right_up: Vec<VirtualKeyCode>,
+    pub right_down: Vec<VirtualKeyCode>,
+    pub serve: Vec<VirtualKeyCode>,
+    pub pause: Vec<VirtualKeyCode>,
+    pub restart: Vec<VirtualKeyCode>,
+    pub quit: Vec<VirtualKeyCode>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            left_up: vec![VirtualKeyCode::W],
+            left_down: vec![VirtualKeyCode::S],
+            right_up: vec![VirtualKeyCode::Up],
+            right_down: vec![VirtualKeyCode::Down],
+            serve: vec![VirtualKeyCode::Return, VirtualKeyCode::Space],
+            pause: vec![VirtualKeyCode::P, VirtualKeyCode::Escape],
+            restart: vec![VirtualKeyCode::R],
+            quit: vec![VirtualKeyCode::Q],
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn keys(&self, action: Action) -> &[VirtualKeyCode] {
+        match action {
+            Action::MOVE(Side::LEFT, MoveCommand::UP) => &self.left_up,
+            Action::MOVE(Side::LEFT, MoveCommand::DOWN) => &self.left_down,
+            Action::MOVE(Side::RIGHT, MoveCommand::UP) => &self.right_up,
+            Action::MOVE(Side::RIGHT, MoveCommand::DOWN) => &self.right_down,
+            Action::MOVE(_, MoveCommand::TARGET(_) | MoveCommand::AXIS(_)) => &[],
+            Action::SERVE => &self.serve,
+            Action::PAUSE => &self.pause,
+            Action::RESTART => &self.restart,
+            Action::QUIT => &self.quit,
+        }
+    }
+
+    pub fn actions(&self, key: VirtualKeyCode) -> Vec<Action> {
+        ALL_ACTIONS
+            .iter()
+            .copied()
+            .filter(|action| self.keys(*action).contains(&key))
+            .collect()
+    }
+
+    pub fn is_held(&self, action: Action, held_keys: &HashSet<VirtualKeyCode>) -> bool {
+        self.keys(action).iter().any(|key| held_keys.contains(key))
+    }
+
+    // The direction held for one side's paddle, None when neither or both directions are held
+    pub fn held_command(&self, side: Side, held_keys: &HashSet<VirtualKeyCode>) -> Option<MoveCommand> {
+        let up = self.is_held(Action::MOVE(side, MoveCommand::UP), held_keys);
+        let down = self.is_held(Action::MOVE(side, MoveCommand::DOWN), held_keys);
+        match (up, down) {
+            (true, false) => Some(MoveCommand::UP),
+            (false, true) => Some(MoveCommand::DOWN),
+            _ => None,
+        }
+    }
+}
+
+const ALL_ACTIONS: [Action; 8] = [
+    Action::MOVE(Side::LEFT, MoveCommand::UP),
+    Action::MOVE(Side::LEFT, MoveCommand::DOWN),
+    Action::MOVE(Side::RIGHT, MoveCommand::UP),
+    Action::MOVE(Side::RIGHT, MoveCommand::DOWN),
+    Action::SERVE,
+    Action::PAUSE,
+    Action::RESTART,
+    Action::QUIT,
+];
@@ -0,0 +1,58 @@
+use gilrs::{Axis, Button, EventType, Gilrs};
+
+use crate::{Action, InputBackend, InputEvent, MoveCommand, Side};
+
+const STICK_DEAD_ZONE: f32 = 0.15;
+
+// The first connected gamepad drives the left paddle and the second one the right paddle
+pub struct GamepadBackend {
+    gilrs: Gilrs,
+    commands: [Option<MoveCommand>; 2]
+}
+
+impl GamepadBackend {
+    pub fn new() -> Result<Self, String> {
+        let gilrs = Gilrs::new().map_err(|error| error.to_string())?;
+        Ok(Self { gilrs, commands: [None; 2] })
+    }
+}
+
+impl InputBackend for GamepadBackend {
+    fn poll(&mut self) -> Vec<InputEvent> {
+        let mut events = Vec::new();
+        while let Some(event) = self.gilrs.next_event() {
+            if let EventType::ButtonPressed(button, _) = event.event {
+                let action = match button {
+                    Button::South => Some(Action::SERVE),
+                    Button::Start => Some(Action::PAUSE),
+                    Button::Select => Some(Action::RESTART),
+                    _ => None
+                };
+                events.extend(action.map(InputEvent::PRESSED));
+            }
+        }
+
+        let mut commands = [None; 2];
+        for (command, (_, gamepad)) in commands.iter_mut().zip(self.gilrs.gamepads()) {
+            let stick = gamepad.value(Axis::LeftStickY);
+            *command = if gamepad.is_pressed(Button::DPadUp) {
+                Some(MoveCommand::UP)
+            } else if gamepad.is_pressed(Button::DPadDown) {
+                Some(MoveCommand::DOWN)
+            } else if stick.abs() > STICK_DEAD_ZONE {
+                Some(MoveCommand::AXIS(stick))
+            } else {
+                None
+            };
+        }
+
+        for (side, index) in [(Side::LEFT, 0), (Side::RIGHT, 1)] {
+            if commands[index] != self.commands[index] {
+                events.push(InputEvent::MOVE(side, commands[index]));
+            }
+        }
+        self.commands = commands;
+
+        events
+    }
+}
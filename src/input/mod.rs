@@ -0,0 +1,15 @@
+mod backend;
+mod bindings;
+#[cfg(feature = "gamepad")]
+mod gamepad;
+mod settings;
+#[cfg(feature = "terminal")]
+mod terminal;
+
+pub use backend::*;
+pub use bindings::*;
+#[cfg(feature = "gamepad")]
+pub use gamepad::*;
+pub use settings::*;
+#[cfg(feature = "terminal")]
+pub use terminal::*;
@@ -0,0 +1,60 @@
+use std::{error::Error, fmt, fs, io, path::Path};
+
+use serde::Deserialize;
+
+use crate::{KeyBindings, Theme, ThemeName};
+
+pub const DEFAULT_SETTINGS_PATH: &str = "settings.toml";
+
+#[derive(Debug)]
+pub enum SettingsError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Io(error) => write!(f, "Failed to read settings: {}", error),
+            SettingsError::Parse(error) => write!(f, "Invalid settings: {}", error),
+        }
+    }
+}
+
+impl Error for SettingsError {}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    pub key_bindings: KeyBindings,
+    // The left paddle follows the mouse cursor while none of its keys are held
+    pub mouse_control: bool,
+    // One of "classic", "neon", "high-contrast" or "colorblind-safe"
+    pub theme: ThemeName,
+    // RGB from 0.0 to 1.0 for the bars around the arena when the window's shape does not match
+    // it, instead of the theme's border color
+    pub border_color: Option<[f32; 3]>,
+}
+
+impl Settings {
+    pub fn theme(&self) -> Theme {
+        let mut theme = self.theme.theme();
+        if let Some(border_color) = self.border_color {
+            theme.border = border_color;
+        }
+        theme
+    }
+
+    pub fn load(path: &Path) -> Result<Self, SettingsError> {
+        let contents = fs::read_to_string(path).map_err(SettingsError::Io)?;
+        toml::from_str(&contents).map_err(SettingsError::Parse)
+    }
+
+    // Falls back to the defaults when there is no settings file, but not when it is invalid
+    pub fn load_or_default(path: &Path) -> Result<Self, SettingsError> {
+        match Self::load(path) {
+            Err(SettingsError::Io(error)) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            result => result,
+        }
+    }
+}
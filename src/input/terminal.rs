@@ -0,0 +1,139 @@
+use std::{collections::{HashMap, HashSet}, io, time::{Duration, Instant}};
+
+use crossterm::{event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags}, execute, terminal};
+use winit::event::VirtualKeyCode;
+
+use crate::{Action, InputBackend, InputEvent, KeyBindings, MoveCommand, Side};
+
+// Most terminals only report key presses and their auto repeats. Without a release event a key
+// counts as held until the OS would have repeated it, which takes longer for the first repeat
+const FIRST_REPEAT_TIMEOUT: Duration = Duration::from_millis(550);
+const REPEAT_TIMEOUT: Duration = Duration::from_millis(100);
+
+// Reads the keyboard from the TTY, e.g. to play with a TerminalRenderer over SSH. Keys are
+// mapped to the window's key codes so that the same KeyBindings apply
+pub struct TerminalBackend {
+    key_bindings: KeyBindings,
+    // When each held key times out, or None once the terminal reports its release
+    held_keys: HashMap<VirtualKeyCode, Option<Instant>>,
+    reports_releases: bool,
+    commands: [Option<MoveCommand>; 2]
+}
+
+impl TerminalBackend {
+    // Expects the terminal to be in raw mode already, e.g. by a TerminalRenderer
+    pub fn new(key_bindings: KeyBindings) -> Self {
+        // Terminals that support the kitty keyboard protocol can report key releases
+        let reports_releases = terminal::supports_keyboard_enhancement().unwrap_or(false)
+            && execute!(io::stdout(), PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)).is_ok();
+
+        Self { key_bindings, held_keys: HashMap::new(), reports_releases, commands: [None; 2] }
+    }
+
+    fn key_event(&mut self, key_event: KeyEvent, events: &mut Vec<InputEvent>) {
+        // Raw mode turns Ctrl+C into a plain key press
+        if key_event.code == KeyCode::Char('c') && key_event.modifiers.contains(KeyModifiers::CONTROL) {
+            events.push(InputEvent::PRESSED(Action::QUIT));
+            return;
+        }
+        let Some(key) = virtual_keycode(key_event.code) else {
+            return;
+        };
+
+        match key_event.kind {
+            KeyEventKind::Press | KeyEventKind::Repeat => {
+                let timeout = if self.reports_releases {
+                    None
+                } else if self.held_keys.contains_key(&key) {
+                    Some(Instant::now() + REPEAT_TIMEOUT)
+                } else {
+                    Some(Instant::now() + FIRST_REPEAT_TIMEOUT)
+                };
+                // Only the first press of a held key triggers its actions
+                if self.held_keys.insert(key, timeout).is_none() {
+                    events.extend(
+                        self.key_bindings
+                            .actions(key)
+                            .into_iter()
+                            .filter(|action| !matches!(action, Action::MOVE(_, _)))
+                            .map(InputEvent::PRESSED)
+                    );
+                }
+            },
+            KeyEventKind::Release => {
+                self.held_keys.remove(&key);
+            }
+        }
+    }
+}
+
+impl InputBackend for TerminalBackend {
+    fn poll(&mut self) -> Vec<InputEvent> {
+        let mut events = Vec::new();
+        while event::poll(Duration::ZERO).unwrap_or(false) {
+            if let Ok(Event::Key(key_event)) = event::read() {
+                self.key_event(key_event, &mut events);
+            }
+        }
+
+        let now = Instant::now();
+        self.held_keys.retain(|_, timeout| timeout.is_none_or(|timeout| timeout > now));
+
+        let held_keys: HashSet<VirtualKeyCode> = self.held_keys.keys().copied().collect();
+        for (side, index) in [(Side::LEFT, 0), (Side::RIGHT, 1)] {
+            let command = self.key_bindings.held_command(side, &held_keys);
+            if command != self.commands[index] {
+                events.push(InputEvent::MOVE(side, command));
+                self.commands[index] = command;
+            }
+        }
+
+        events
+    }
+}
+
+impl Drop for TerminalBackend {
+    fn drop(&mut self) {
+        if self.reports_releases {
+            let _ = execute!(io::stdout(), PopKeyboardEnhancementFlags);
+        }
+    }
+}
+
+fn virtual_keycode(code: KeyCode) -> Option<VirtualKeyCode> {
+    let key = match code {
+        KeyCode::Up => VirtualKeyCode::Up,
+        KeyCode::Down => VirtualKeyCode::Down,
+        KeyCode::Left => VirtualKeyCode::Left,
+        KeyCode::Right => VirtualKeyCode::Right,
+        KeyCode::Enter => VirtualKeyCode::Return,
+        KeyCode::Esc => VirtualKeyCode::Escape,
+        KeyCode::Tab => VirtualKeyCode::Tab,
+        KeyCode::Backspace => VirtualKeyCode::Back,
+        KeyCode::Char(' ') => VirtualKeyCode::Space,
+        KeyCode::Char(character) => return char_keycode(character.to_ascii_uppercase()),
+        _ => return None,
+    };
+    Some(key)
+}
+
+fn char_keycode(character: char) -> Option<VirtualKeyCode> {
+    const LETTERS: [VirtualKeyCode; 26] = [
+        VirtualKeyCode::A, VirtualKeyCode::B, VirtualKeyCode::C, VirtualKeyCode::D, VirtualKeyCode::E,
+        VirtualKeyCode::F, VirtualKeyCode::G, VirtualKeyCode::H, VirtualKeyCode::I, VirtualKeyCode::J,
+        VirtualKeyCode::K, VirtualKeyCode::L, VirtualKeyCode::M, VirtualKeyCode::N, VirtualKeyCode::O,
+        VirtualKeyCode::P, VirtualKeyCode::Q, VirtualKeyCode::R, VirtualKeyCode::S, VirtualKeyCode::T,
+        VirtualKeyCode::U, VirtualKeyCode::V, VirtualKeyCode::W, VirtualKeyCode::X, VirtualKeyCode::Y,
+        VirtualKeyCode::Z,
+    ];
+    const DIGITS: [VirtualKeyCode; 10] = [
+        VirtualKeyCode::Key0, VirtualKeyCode::Key1, VirtualKeyCode::Key2, VirtualKeyCode::Key3, VirtualKeyCode::Key4,
+        VirtualKeyCode::Key5, VirtualKeyCode::Key6, VirtualKeyCode::Key7, VirtualKeyCode::Key8, VirtualKeyCode::Key9,
+    ];
+
+    match character {
+        'A'..='Z' => Some(LETTERS[character as usize - 'A' as usize]),
+        '0'..='9' => Some(DIGITS[character as usize - '0' as usize]),
+        _ => None,
+    }
+}
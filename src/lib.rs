@@ -1,7 +1,19 @@
 mod model;
 pub mod render_engine;
-mod constants;
+mod simulation;
+mod replay;
+mod input;
+mod config;
 
 pub use model::*;
-pub use render_engine::RenderEngine;
-pub use constants::*;
\ No newline at end of file
+pub use render_engine::{hud_labels, Frame, Label, Renderer, RendererKind, SoftwareRenderer, Theme, ThemeName, WindowSettings};
+#[cfg(feature = "vulkan")]
+pub use render_engine::{OffscreenRenderer, RenderEngine};
+#[cfg(feature = "window")]
+pub use render_engine::SoftwareWindowRenderer;
+#[cfg(feature = "terminal")]
+pub use render_engine::TerminalRenderer;
+pub use simulation::*;
+pub use replay::*;
+pub use input::*;
+pub use config::*;
\ No newline at end of file
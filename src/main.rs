@@ -1,48 +1,118 @@
-use std::{cell::RefCell, rc::Rc, time::SystemTime};
+mod cli;
 
-use pong::{Ball, GameObject, GameObjectState, MoveCommand, Paddle, PaddleType, RenderEngine};
-use winit::{event::{Event, KeyboardInput, VirtualKeyCode, WindowEvent}, event_loop::{ControlFlow, EventLoop}};
+use std::{collections::HashSet, fmt::Display, mem, path::Path, process, thread, time::{Duration, Instant, SystemTime}};
+
+use clap::Parser;
+use cli::Cli;
+
+use pong::{apply_action, apply_event, hud_labels, paddle_commands, ControllerKind, GameConfig, InputBackend, KeyBindings, MatchSetup, MoveCommand, OffscreenRenderer, Phase, RenderEngine, Renderer, RendererKind, Replay, Settings, Side, Simulation, SoftwareRenderer, SoftwareWindowRenderer, TerminalBackend, TerminalRenderer, Theme, TickInput};
+use winit::{event::{ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEvent}, event_loop::{ControlFlow, EventLoop}};
+
+const MS_PER_UPDATE: u128 = 17;
 
 fn main() {
+    let cli = Cli::parse();
+    let mut settings = Settings::load_or_default(&cli.settings).unwrap_or_else(|error| exit_with_error(error));
+    if let Some(theme) = cli.theme {
+        settings.theme = theme;
+    }
+    let theme = settings.theme();
+    let key_bindings = settings.key_bindings;
+    let mouse_control = settings.mouse_control || cli.mouse;
+
+    let mut playback = cli.replay.as_ref().map(|path| {
+        let replay = Replay::load(path).unwrap_or_else(|error| exit_with_error(error));
+        (replay.setup, replay.inputs.into_iter())
+    });
+
+    let setup = match &playback {
+        Some((setup, _)) => *setup,
+        None => {
+            let mut config = match &cli.config {
+                Some(path) => GameConfig::load(path).unwrap_or_else(|error| exit_with_error(error)),
+                None => GameConfig::default()
+            };
+            if let Some(points_to_win) = cli.points_to_win {
+                config.rules.points_to_win = points_to_win;
+                config.validate().unwrap_or_else(|error| exit_with_error(error));
+            }
+
+            let (left_controller, right_controller) = cli.controllers();
+            MatchSetup {
+                seed: cli.seed.unwrap_or_else(rand::random),
+                config,
+                left_controller,
+                right_controller
+            }
+        }
+    };
+    println!("Seed: {}", setup.seed);
+
+    let record_path = cli.record.clone();
+    let mut recording = record_path.as_ref().map(|_| Replay::new(setup));
+
+    if cli.headless {
+        let simulation = run_headless(setup, &theme, playback.map(|(_, inputs)| inputs), recording.as_mut(), cli.ticks);
+        let score = simulation.game_state().score();
+        println!(
+            "Score: {} - {} after {} ticks",
+            score.points(Side::LEFT),
+            score.points(Side::RIGHT),
+            simulation.ticks()
+        );
+        if let (Some(recording), Some(path)) = (&recording, &record_path) {
+            save_recording(recording, path);
+        }
+        if let Some(path) = &cli.screenshot {
+            let (width, height) = cli.screenshot_size();
+            let labels = hud_labels(simulation.game_state(), &setup.config.arena);
+            let frame = match cli.renderer {
+                RendererKind::VULKAN => {
+                    OffscreenRenderer::new(width, height, setup.config.arena, theme)
+                        .render(simulation.game_objects(), &labels)
+                },
+                RendererKind::SOFTWARE | RendererKind::TERMINAL => {
+                    let mut renderer = SoftwareRenderer::new(width, height, setup.config.arena, theme);
+                    renderer.draw(simulation.game_objects(), &labels);
+                    renderer.frame().clone()
+                }
+            };
+            match frame.save_png(path) {
+                Ok(()) => println!("Screenshot saved to {}", path.display()),
+                Err(error) => {
+                    eprintln!("Failed to save screenshot: {}", error);
+                    process::exit(1);
+                }
+            }
+        }
+        return;
+    }
+
+    let versus = setup.right_controller == ControllerKind::HUMAN;
+
+    if cli.renderer == RendererKind::TERMINAL {
+        run_terminal(setup, playback.map(|(_, inputs)| inputs), recording.as_mut(), &key_bindings, theme, versus);
+        if let (Some(recording), Some(path)) = (&recording, &record_path) {
+            save_recording(recording, path);
+        }
+        return;
+    }
+
     let event_loop = EventLoop::new();
-    let mut render_engine = RenderEngine::new(&event_loop);
-
-    let left_paddle = Paddle::new(
-        GameObjectState { 
-            height: 10.0, 
-            width: 2.0,
-            x: 0.0, 
-            y: 50.0, 
-        }, 
-        PaddleType::PLAYER
-    );
-    let ball = Ball::new(
-        GameObjectState { 
-            height: 2.0, 
-            width: 2.0,
-            x: 50.0, 
-            y: 50.0, 
-        }, 
-        1.0,
-        0.0
-    );
-    let right_paddle = Paddle::new(
-        GameObjectState { 
-            height: 10.0, 
-            width: 2.0,
-            x: 98.0, 
-            y: 50.0, 
-        },
-        PaddleType::AI
-    );
+    let window_settings = cli.window_settings(theme);
+    let mut renderer: Box<dyn Renderer> = match cli.renderer {
+        RendererKind::VULKAN => Box::new(RenderEngine::new(&event_loop, window_settings, setup.config.arena)),
+        RendererKind::SOFTWARE => Box::new(SoftwareWindowRenderer::new(&event_loop, window_settings, setup.config.arena)),
+        RendererKind::TERMINAL => unreachable!("The terminal renderer runs without a window"),
+    };
 
-    let game_objects: Vec<Rc<RefCell<dyn GameObject>>> = vec![
-        Rc::new(RefCell::new(left_paddle)),
-        Rc::new(RefCell::new(ball)),
-        Rc::new(RefCell::new(right_paddle))
-    ];
+    let mut simulation = Simulation::with_theme(setup, &theme);
+    let mut tick_input = TickInput::default();
+    let mut held_keys: HashSet<VirtualKeyCode> = HashSet::new();
+    let mut cursor_y: Option<f32> = None;
+    let mut input_backends = input_backends();
+    let mut device_commands: [Option<MoveCommand>; 2] = [None; 2];
 
-    let ms_per_update: u128 = 17;
     let mut previous = SystemTime::now();
     let mut lag: u128 = 0;
 
@@ -58,7 +128,7 @@ fn main() {
                 event: WindowEvent::Resized(_),
                 ..
             } => {
-                render_engine.on_window_resized();
+                renderer.on_window_resized();
             }
             Event::MainEventsCleared => { // Main game loop
                 let current = SystemTime::now();
@@ -68,50 +138,205 @@ fn main() {
                 previous = current;
                 lag += elapsed.as_millis();
 
-                while lag >= ms_per_update {
-                    update(&game_objects);
-                    lag -= ms_per_update
+                for event in input_backends.iter_mut().flat_map(|backend| backend.poll()) {
+                    if apply_event(event, &mut device_commands, &mut tick_input) {
+                        *control_flow = ControlFlow::Exit;
+                    }
+                }
+
+                while lag >= MS_PER_UPDATE {
+                    let input = match &mut playback {
+                        Some((_, inputs)) => inputs.next(),
+                        None => {
+                            let mut input = mem::take(&mut tick_input);
+                            (input.left_paddle, input.right_paddle) = paddle_commands(&key_bindings, &held_keys, device_commands, versus);
+                            if mouse_control && input.left_paddle.is_none() {
+                                input.left_paddle = cursor_y.map(MoveCommand::TARGET);
+                            }
+                            Some(input)
+                        }
+                    };
+
+                    if let Some(input) = input {
+                        if let Some(recording) = &mut recording {
+                            recording.record(&input);
+                        }
+                        simulation.step(input);
+                    }
+                    lag -= MS_PER_UPDATE
                 }
-                render_engine.draw(&game_objects);
+                renderer.draw(simulation.game_objects(), &hud_labels(simulation.game_state(), &setup.config.arena));
             },
+            Event::WindowEvent {
+                event: WindowEvent::CursorMoved { position, .. },
+                ..
+            } => {
+                cursor_y = Some(renderer.window_to_game_units(position).1);
+            }
+            Event::WindowEvent {
+                event: WindowEvent::Focused(false),
+                ..
+            } => {
+                // Keys released while another window has focus never report their release
+                held_keys.clear();
+            }
             Event::WindowEvent {
                 event: WindowEvent::KeyboardInput { input, .. },
                 ..
             } => {
-                let player_paddle = game_objects.iter().find(|game_object| {
-                    if let Some(paddle) = game_object.borrow_mut().as_paddle() {
-                        return paddle.paddle_type == PaddleType::PLAYER;
-                    }
-                    false
-                }).
-                expect("No player paddle found");
-                handle_keyboard_input(input, player_paddle.borrow_mut().as_paddle().unwrap());
+                let quit = handle_keyboard_input(input, &key_bindings, &mut held_keys, &mut tick_input);
+                if quit {
+                    *control_flow = ControlFlow::Exit;
+                }
+            }
+            Event::LoopDestroyed => {
+                if let (Some(recording), Some(path)) = (&recording, &record_path) {
+                    save_recording(recording, path);
+                }
             }
             _ => ()
         }
     });
 }
 
-fn update(game_objects: &Vec<Rc<RefCell<dyn GameObject>>>) {
-    for game_object in game_objects {
-        game_object.borrow_mut().update(game_objects);
+// Serves straight away and then runs without player input until the match is over, the
+// replay runs out or the tick limit is reached
+fn run_headless(
+    setup: MatchSetup,
+    theme: &Theme,
+    mut playback: Option<impl Iterator<Item = TickInput>>,
+    mut recording: Option<&mut Replay>,
+    max_ticks: Option<u64>
+) -> Simulation {
+    let mut simulation = Simulation::with_theme(setup, theme);
+
+    while max_ticks.is_none_or(|max_ticks| simulation.ticks() < max_ticks) {
+        let input = match &mut playback {
+            Some(inputs) => match inputs.next() {
+                Some(input) => input,
+                None => break
+            },
+            None if simulation.game_state().phase() == Phase::FINISHED => break,
+            None => TickInput { serve: simulation.ticks() == 0, ..TickInput::default() }
+        };
+
+        if let Some(recording) = &mut recording {
+            recording.record(&input);
+        }
+        simulation.step(input);
     }
+
+    simulation
 }
 
-fn handle_keyboard_input(keyboard_input: KeyboardInput, player_paddel: &mut Paddle) {
-    match keyboard_input {
-        KeyboardInput {
-            virtual_keycode: Some(VirtualKeyCode::Up),
-            ..
-        } => {
-            player_paddel.move_commands.push_back(MoveCommand::UP);
-        },
-        KeyboardInput {
-            virtual_keycode: Some(VirtualKeyCode::Down),
-            ..
-        } => {
-            player_paddel.move_commands.push_back(MoveCommand::DOWN);
-        },
-        _ => ()
+// Plays in the terminal until quitting, with the keyboard read from the TTY. Each frame steps
+// the simulation once and then sleeps for the rest of the tick
+fn run_terminal(
+    setup: MatchSetup,
+    mut playback: Option<impl Iterator<Item = TickInput>>,
+    mut recording: Option<&mut Replay>,
+    key_bindings: &KeyBindings,
+    theme: Theme,
+    versus: bool
+) {
+    // Created before the terminal switches screens so that their messages stay visible
+    let mut input_backends = input_backends();
+    let mut renderer = TerminalRenderer::new(setup.config.arena, theme).expect("Failed to set up the terminal");
+    // Declared after the renderer so that it is dropped first and restores the keyboard mode
+    // before the renderer leaves raw mode
+    let mut terminal_backend = TerminalBackend::new(key_bindings.clone());
+
+    let mut simulation = Simulation::with_theme(setup, &theme);
+    let mut tick_input = TickInput::default();
+    let mut device_commands: [Option<MoveCommand>; 2] = [None; 2];
+    let tick_duration = Duration::from_millis(MS_PER_UPDATE as u64);
+    let mut next_tick = Instant::now();
+
+    loop {
+        let events = input_backends.iter_mut().flat_map(|backend| backend.poll()).chain(terminal_backend.poll());
+        for event in events {
+            if apply_event(event, &mut device_commands, &mut tick_input) {
+                return;
+            }
+        }
+
+        let input = match &mut playback {
+            Some(inputs) => inputs.next(),
+            None => {
+                let mut input = mem::take(&mut tick_input);
+                (input.left_paddle, input.right_paddle) = paddle_commands(key_bindings, &HashSet::new(), device_commands, versus);
+                Some(input)
+            }
+        };
+        if let Some(input) = input {
+            if let Some(recording) = &mut recording {
+                recording.record(&input);
+            }
+            simulation.step(input);
+        }
+        renderer.draw(simulation.game_objects(), &hud_labels(simulation.game_state(), &setup.config.arena));
+
+        next_tick += tick_duration;
+        thread::sleep(next_tick.saturating_duration_since(Instant::now()));
+    }
+}
+
+// For errors in the user's own files, which are not bugs and need no backtrace
+fn exit_with_error(error: impl Display) -> ! {
+    eprintln!("{}", error);
+    process::exit(1);
+}
+
+fn save_recording(recording: &Replay, path: &Path) {
+    match recording.save(path) {
+        Ok(()) => println!("Replay saved to {}", path.display()),
+        Err(error) => eprintln!("{}", error)
+    }
+}
+
+// Returns whether quitting was requested
+fn handle_keyboard_input(
+    keyboard_input: KeyboardInput,
+    key_bindings: &KeyBindings,
+    held_keys: &mut HashSet<VirtualKeyCode>,
+    tick_input: &mut TickInput
+) -> bool {
+    let Some(key) = keyboard_input.virtual_keycode else {
+        return false;
     };
+
+    match keyboard_input.state {
+        ElementState::Pressed => {
+            // Ignore the OS key repeat of a key that is already held
+            if !held_keys.insert(key) {
+                return false;
+            }
+
+            let mut quit = false;
+            for action in key_bindings.actions(key) {
+                quit |= apply_action(action, tick_input);
+            }
+            quit
+        },
+        ElementState::Released => {
+            held_keys.remove(&key);
+            false
+        }
+    }
+}
+
+#[cfg(feature = "gamepad")]
+fn input_backends() -> Vec<Box<dyn InputBackend>> {
+    match pong::GamepadBackend::new() {
+        Ok(backend) => vec![Box::new(backend)],
+        Err(error) => {
+            eprintln!("Gamepads unavailable: {}", error);
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(not(feature = "gamepad"))]
+fn input_backends() -> Vec<Box<dyn InputBackend>> {
+    Vec::new()
 }
@@ -0,0 +1,207 @@
+use std::str::FromStr;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{Arena, Ball, GameObject, GameObjectState, PaddleMotion};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Difficulty {
+    EASY,
+    MEDIUM,
+    HARD,
+    IMPOSSIBLE
+}
+
+impl Difficulty {
+    pub fn profile(&self) -> AiProfile {
+        match self {
+            Difficulty::EASY => AiProfile { reaction_ticks: 15, max_speed: 0.5, prediction: 0.0, aim_error: 5.0 },
+            Difficulty::MEDIUM => AiProfile { reaction_ticks: 10, max_speed: 0.7, prediction: 0.5, aim_error: 4.0 },
+            Difficulty::HARD => AiProfile { reaction_ticks: 4, max_speed: 0.85, prediction: 1.0, aim_error: 1.5 },
+            Difficulty::IMPOSSIBLE => AiProfile { reaction_ticks: 0, max_speed: 1.0, prediction: 1.0, aim_error: 0.0 },
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::EASY => "EASY",
+            Difficulty::MEDIUM => "MEDIUM",
+            Difficulty::HARD => "HARD",
+            Difficulty::IMPOSSIBLE => "IMPOSSIBLE",
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "EASY" => Ok(Difficulty::EASY),
+            "MEDIUM" => Ok(Difficulty::MEDIUM),
+            "HARD" => Ok(Difficulty::HARD),
+            "IMPOSSIBLE" => Ok(Difficulty::IMPOSSIBLE),
+            _ => Err(format!("Unknown difficulty {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct AiProfile {
+    // Ticks between decisions about where to move
+    pub reaction_ticks: u32,
+    // Fastest the AI moves the paddle, as a fraction of the paddle's own max speed
+    pub max_speed: f32,
+    // How far along the ball's path to look, from 0 (current position) to 1 (where it reaches the paddle)
+    pub prediction: f32,
+    // Largest random offset added to the chosen target
+    pub aim_error: f32,
+}
+
+#[derive(Debug)]
+pub struct AiController {
+    profile: AiProfile,
+    rng: StdRng,
+    target_y: Option<f32>,
+    ticks_until_reaction: u32,
+}
+
+impl AiController {
+    pub fn new(difficulty: Difficulty, seed: u64) -> Self {
+        Self {
+            profile: difficulty.profile(),
+            rng: StdRng::seed_from_u64(seed),
+            target_y: None,
+            ticks_until_reaction: 0,
+        }
+    }
+
+    pub fn profile(&self) -> &AiProfile {
+        &self.profile
+    }
+
+    // Returns how hard to push the paddle towards its target, like PaddleController::next_move
+    pub fn next_move_towards(&mut self, paddle: &GameObjectState, motion: &PaddleMotion, ball: &Ball, arena: &Arena) -> f32 {
+        if self.ticks_until_reaction == 0 {
+            self.target_y = Some(self.choose_target(paddle, ball, arena));
+            self.ticks_until_reaction = self.profile.reaction_ticks;
+        } else {
+            self.ticks_until_reaction -= 1;
+        }
+
+        let Some(target_y) = self.target_y else {
+            return 0.0;
+        };
+
+        // Slows down to stop on the target rather than overshoot it
+        let distance = target_y - (paddle.y + paddle.height / 2.0);
+        (distance / motion.max_speed).clamp(-self.profile.max_speed, self.profile.max_speed)
+    }
+
+    fn choose_target(&mut self, paddle: &GameObjectState, ball: &Ball, arena: &Arena) -> f32 {
+        let ball_state = ball.get_state();
+        let ball_center_y = ball_state.y + ball_state.height / 2.0;
+
+        let target_y = match predict_intercept(ball, paddle, arena) {
+            Some(intercept_y) => ball_center_y + (intercept_y - ball_center_y) * self.profile.prediction,
+            None if self.profile.prediction > 0.0 => arena.height / 2.0,
+            None => ball_center_y,
+        };
+
+        let aim_error = if self.profile.aim_error > 0.0 {
+            self.rng.random_range(-self.profile.aim_error..=self.profile.aim_error)
+        } else {
+            0.0
+        };
+
+        (target_y + aim_error).clamp(0.0, arena.height)
+    }
+}
+
+// Projects the ball's path, including bounces off the bottom and top walls, to the face of
+// `paddle` and returns the y of the ball's center when it gets there. Returns None when the
+// ball is not travelling towards the paddle.
+pub fn predict_intercept(ball: &Ball, paddle: &GameObjectState, arena: &Arena) -> Option<f32> {
+    let ball_state = ball.get_state();
+    let (velocity_x, velocity_y) = ball.velocity();
+
+    let target_x = if velocity_x > 0.0 && paddle.x >= ball_state.x + ball_state.width {
+        paddle.x - ball_state.width
+    } else if velocity_x < 0.0 && paddle.x + paddle.width <= ball_state.x {
+        paddle.x + paddle.width
+    } else {
+        return None;
+    };
+
+    let ticks = (target_x - ball_state.x) / velocity_x;
+    let unbounded_y = ball_state.y + velocity_y * ticks;
+
+    // Unfold the reflections: the ball's bottom edge travels between 0 and the arena's height - ball height
+    let travel = arena.height - ball_state.height;
+    if travel <= 0.0 {
+        return Some(arena.height / 2.0);
+    }
+    let mut folded = unbounded_y.rem_euclid(2.0 * travel);
+    if folded > travel {
+        folded = 2.0 * travel - folded;
+    }
+
+    Some(folded + ball_state.height / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BallSpeed;
+
+    const ARENA: Arena = Arena { width: 40.0, height: 20.0 };
+
+    // A 1x1 ball whose center starts at (10.5, 5.5), so it meets the right paddle's face after
+    // 28 ticks at a horizontal speed of 1
+    fn ball(velocity_x: f32, velocity_y: f32) -> Ball {
+        let mut ball = Ball::new(GameObjectState { height: 1.0, width: 1.0, x: 10.0, y: 5.0, color: [1.0; 3] }, BallSpeed::default());
+        ball.set_velocity(velocity_x, velocity_y);
+        ball
+    }
+
+    fn right_paddle() -> GameObjectState {
+        GameObjectState { height: 4.0, width: 1.0, x: 39.0, y: 8.0, color: [1.0; 3] }
+    }
+
+    #[test]
+    fn speed_scales_with_difficulty() {
+        // Well above the center of the arena, where the AI heads while the ball moves away
+        let paddle = GameObjectState { height: 4.0, width: 1.0, x: 39.0, y: 14.0, color: [1.0; 3] };
+        let motion = PaddleMotion::default();
+
+        let speeds: Vec<f32> = [Difficulty::EASY, Difficulty::MEDIUM, Difficulty::HARD, Difficulty::IMPOSSIBLE]
+            .into_iter()
+            .map(|difficulty| AiController::new(difficulty, 0).next_move_towards(&paddle, &motion, &ball(-1.0, 0.0), &ARENA))
+            .collect();
+
+        assert_eq!(speeds, [-0.5, -0.7, -0.85, -1.0]);
+    }
+
+    #[test]
+    fn intercept_without_bounce() {
+        assert_eq!(predict_intercept(&ball(1.0, 0.25), &right_paddle(), &ARENA), Some(12.5));
+    }
+
+    #[test]
+    fn intercept_after_one_bounce() {
+        // Travels up to the top wall at y = 19 and 14 units back down
+        assert_eq!(predict_intercept(&ball(1.0, 1.0), &right_paddle(), &ARENA), Some(5.5));
+    }
+
+    #[test]
+    fn intercept_after_several_bounces() {
+        // Bounces off the bottom, the top and the bottom wall again
+        assert_eq!(predict_intercept(&ball(1.0, -2.0), &right_paddle(), &ARENA), Some(13.5));
+    }
+
+    #[test]
+    fn no_intercept_when_moving_away() {
+        assert_eq!(predict_intercept(&ball(-1.0, 0.5), &right_paddle(), &ARENA), None);
+        assert_eq!(predict_intercept(&ball(0.0, 0.5), &right_paddle(), &ARENA), None);
+    }
+}
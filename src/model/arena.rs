@@ -0,0 +1,15 @@
+use serde::Deserialize;
+
+// The playing field spans 0..width horizontally and 0..height vertically, in game units
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Arena {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Self { width: 100.0, height: 100.0 }
+    }
+}
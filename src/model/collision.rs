@@ -0,0 +1,136 @@
+use crate::GameObjectState;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Hit {
+    pub time: f32,
+    pub normal_x: f32,
+    pub normal_y: f32,
+}
+
+// Sweeps `moving` by (x_distance, y_distance) against the static `target` and returns the
+// fraction of the move at which they first touch, along with the surface normal of `target`.
+pub fn sweep_aabb(moving: &GameObjectState, x_distance: f32, y_distance: f32, target: &GameObjectState) -> Option<Hit> {
+    // Expand the target by the moving box so the moving box can be treated as a point
+    let min_x = target.x - moving.width;
+    let max_x = target.x + target.width;
+    let min_y = target.y - moving.height;
+    let max_y = target.y + target.height;
+
+    if moving.x > min_x && moving.x < max_x && moving.y > min_y && moving.y < max_y {
+        // Already overlapping, e.g. a paddle moved onto the ball. Push back out horizontally.
+        let normal_x = if moving.x + moving.width / 2.0 < target.x + target.width / 2.0 { -1.0 } else { 1.0 };
+        if x_distance * normal_x < 0.0 {
+            return Some(Hit { time: 0.0, normal_x, normal_y: 0.0 });
+        }
+        return None;
+    }
+
+    let (x_entry, x_exit) = axis_times(moving.x, x_distance, min_x, max_x)?;
+    let (y_entry, y_exit) = axis_times(moving.y, y_distance, min_y, max_y)?;
+
+    let entry = x_entry.max(y_entry);
+    let exit = x_exit.min(y_exit);
+    if entry > exit || !(0.0..=1.0).contains(&entry) || exit <= 0.0 {
+        return None;
+    }
+
+    if x_entry > y_entry {
+        Some(Hit { time: entry, normal_x: -x_distance.signum(), normal_y: 0.0 })
+    } else {
+        Some(Hit { time: entry, normal_x: 0.0, normal_y: -y_distance.signum() })
+    }
+}
+
+// Returns the first time `moving` touches the bottom or top bound when moved by y_distance
+pub fn sweep_vertical_bounds(moving: &GameObjectState, y_distance: f32, min_y: f32, max_y: f32) -> Option<Hit> {
+    let time = if y_distance < 0.0 {
+        (min_y - moving.y) / y_distance
+    } else if y_distance > 0.0 {
+        (max_y - (moving.y + moving.height)) / y_distance
+    } else {
+        return None;
+    };
+
+    if (0.0..=1.0).contains(&time) {
+        Some(Hit { time, normal_x: 0.0, normal_y: -y_distance.signum() })
+    } else {
+        None
+    }
+}
+
+fn axis_times(position: f32, distance: f32, min: f32, max: f32) -> Option<(f32, f32)> {
+    if distance > 0.0 {
+        Some(((min - position) / distance, (max - position) / distance))
+    } else if distance < 0.0 {
+        Some(((max - position) / distance, (min - position) / distance))
+    } else if position > min && position < max {
+        Some((f32::NEG_INFINITY, f32::INFINITY))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> GameObjectState {
+        GameObjectState { height, width, x, y, color: [1.0; 3] }
+    }
+
+    fn paddle() -> GameObjectState {
+        rect(10.0, 0.0, 0.5, 2.0)
+    }
+
+    #[test]
+    fn fast_ball_does_not_tunnel_through_paddle() {
+        // The ball ends the tick far past the paddle without ever overlapping it at a tick
+        let ball = rect(5.0, 0.5, 0.5, 0.5);
+
+        let hit = sweep_aabb(&ball, 20.0, 0.0, &paddle()).unwrap();
+
+        assert_eq!(hit.time, 0.225);
+        assert_eq!((hit.normal_x, hit.normal_y), (-1.0, 0.0));
+    }
+
+    #[test]
+    fn hits_top_face() {
+        let ball = rect(10.0, 4.0, 0.5, 0.5);
+
+        let hit = sweep_aabb(&ball, 0.0, -4.0, &paddle()).unwrap();
+
+        assert_eq!(hit.time, 0.5);
+        assert_eq!((hit.normal_x, hit.normal_y), (0.0, 1.0));
+    }
+
+    #[test]
+    fn pushes_overlapping_ball_out() {
+        // Overlaps the right half of the paddle
+        let ball = rect(10.1, 1.0, 0.5, 0.5);
+
+        let hit = sweep_aabb(&ball, -1.0, 0.0, &paddle()).unwrap();
+        assert_eq!(hit.time, 0.0);
+        assert_eq!((hit.normal_x, hit.normal_y), (1.0, 0.0));
+
+        // Already moving out of the paddle
+        assert!(sweep_aabb(&ball, 1.0, 0.0, &paddle()).is_none());
+    }
+
+    #[test]
+    fn corner_hit_uses_vertical_normal() {
+        // Reaches the paddle's bottom left corner on both axes at once
+        let ball = rect(8.5, -2.5, 0.5, 0.5);
+
+        let hit = sweep_aabb(&ball, 2.0, 4.0, &paddle()).unwrap();
+
+        assert_eq!(hit.time, 0.5);
+        assert_eq!((hit.normal_x, hit.normal_y), (0.0, -1.0));
+    }
+
+    #[test]
+    fn misses_when_passing_by() {
+        let ball = rect(5.0, 3.0, 0.5, 0.5);
+
+        assert!(sweep_aabb(&ball, 20.0, 0.0, &paddle()).is_none());
+    }
+}
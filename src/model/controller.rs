@@ -0,0 +1,190 @@
+use std::{cell::RefCell, collections::VecDeque, rc::Rc, str::FromStr, sync::mpsc::Receiver};
+
+use crate::{find_ball, AiController, Arena, Ball, Difficulty, GameObject, GameObjectState, MoveCommand, PaddleMotion};
+
+pub trait PaddleController {
+    // Returns how hard to push the paddle this tick, from -1 (full speed down) to 1 (full speed up).
+    // `command` is the direction held on the paddle's input this tick, if any.
+    fn next_move(
+        &mut self,
+        paddle: &GameObjectState,
+        motion: &PaddleMotion,
+        command: Option<MoveCommand>,
+        arena: &Arena,
+        game_objects: &[Rc<RefCell<dyn GameObject>>]
+    ) -> f32;
+
+    // Called instead of next_move on ticks where paddles are not updated, e.g. while paused
+    fn skip_tick(&mut self) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ControllerKind {
+    HUMAN,
+    AI(Difficulty)
+}
+
+impl ControllerKind {
+    pub fn controller(&self, seed: u64) -> Box<dyn PaddleController> {
+        match self {
+            ControllerKind::HUMAN => Box::new(HumanController),
+            ControllerKind::AI(difficulty) => Box::new(AiController::new(*difficulty, seed)),
+        }
+    }
+}
+
+// Who controls each paddle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    // Left paddle against the AI
+    SINGLE,
+    // Two players on one keyboard
+    VERSUS,
+    // AI against AI
+    DEMO
+}
+
+impl Mode {
+    // The left and right paddles' controllers
+    pub fn controllers(&self, difficulty: Difficulty) -> (ControllerKind, ControllerKind) {
+        match self {
+            Mode::SINGLE => (ControllerKind::HUMAN, ControllerKind::AI(difficulty)),
+            Mode::VERSUS => (ControllerKind::HUMAN, ControllerKind::HUMAN),
+            Mode::DEMO => (ControllerKind::AI(difficulty), ControllerKind::AI(difficulty)),
+        }
+    }
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "SINGLE" => Ok(Mode::SINGLE),
+            "VERSUS" => Ok(Mode::VERSUS),
+            "DEMO" => Ok(Mode::DEMO),
+            _ => Err(format!("Unknown mode {}", s)),
+        }
+    }
+}
+
+fn command_axis(command: Option<MoveCommand>, paddle: &GameObjectState, motion: &PaddleMotion) -> f32 {
+    match command {
+        Some(MoveCommand::UP) => 1.0,
+        Some(MoveCommand::DOWN) => -1.0,
+        Some(MoveCommand::TARGET(y)) => (y - (paddle.y + paddle.height / 2.0)) / motion.max_speed,
+        Some(MoveCommand::AXIS(axis)) => axis,
+        None => 0.0,
+    }
+}
+
+pub struct HumanController;
+
+impl PaddleController for HumanController {
+    fn next_move(
+        &mut self,
+        paddle: &GameObjectState,
+        motion: &PaddleMotion,
+        command: Option<MoveCommand>,
+        _arena: &Arena,
+        _game_objects: &[Rc<RefCell<dyn GameObject>>]
+    ) -> f32 {
+        command_axis(command, paddle, motion)
+    }
+}
+
+impl PaddleController for AiController {
+    fn next_move(
+        &mut self,
+        paddle: &GameObjectState,
+        motion: &PaddleMotion,
+        _command: Option<MoveCommand>,
+        arena: &Arena,
+        game_objects: &[Rc<RefCell<dyn GameObject>>]
+    ) -> f32 {
+        let ball = find_ball(game_objects);
+        self.next_move_towards(paddle, motion, ball.as_ball().unwrap(), arena)
+    }
+}
+
+// Plays back one paddle's recorded commands, one entry per tick
+pub struct ReplayController {
+    ticks: VecDeque<Option<MoveCommand>>,
+}
+
+impl ReplayController {
+    pub fn new(ticks: Vec<Option<MoveCommand>>) -> Self {
+        Self { ticks: ticks.into() }
+    }
+}
+
+impl PaddleController for ReplayController {
+    fn next_move(
+        &mut self,
+        paddle: &GameObjectState,
+        motion: &PaddleMotion,
+        _command: Option<MoveCommand>,
+        _arena: &Arena,
+        _game_objects: &[Rc<RefCell<dyn GameObject>>]
+    ) -> f32 {
+        command_axis(self.ticks.pop_front().flatten(), paddle, motion)
+    }
+
+    fn skip_tick(&mut self) {
+        self.ticks.pop_front();
+    }
+}
+
+// Follows the direction held by a remote peer, e.g. sent by a socket thread whenever it changes
+pub struct NetworkController {
+    receiver: Receiver<Option<MoveCommand>>,
+    command: Option<MoveCommand>,
+}
+
+impl NetworkController {
+    pub fn new(receiver: Receiver<Option<MoveCommand>>) -> Self {
+        Self { receiver, command: None }
+    }
+}
+
+impl PaddleController for NetworkController {
+    fn next_move(
+        &mut self,
+        paddle: &GameObjectState,
+        motion: &PaddleMotion,
+        _command: Option<MoveCommand>,
+        _arena: &Arena,
+        _game_objects: &[Rc<RefCell<dyn GameObject>>]
+    ) -> f32 {
+        if let Some(command) = self.receiver.try_iter().last() {
+            self.command = command;
+        }
+        command_axis(self.command, paddle, motion)
+    }
+}
+
+type Script = Box<dyn FnMut(&GameObjectState, &Ball) -> f32>;
+
+pub struct ScriptController {
+    script: Script,
+}
+
+impl ScriptController {
+    pub fn new(script: impl FnMut(&GameObjectState, &Ball) -> f32 + 'static) -> Self {
+        Self { script: Box::new(script) }
+    }
+}
+
+impl PaddleController for ScriptController {
+    fn next_move(
+        &mut self,
+        paddle: &GameObjectState,
+        _motion: &PaddleMotion,
+        _command: Option<MoveCommand>,
+        _arena: &Arena,
+        game_objects: &[Rc<RefCell<dyn GameObject>>]
+    ) -> f32 {
+        let ball = find_ball(game_objects);
+        (self.script)(paddle, ball.as_ball().unwrap())
+    }
+}
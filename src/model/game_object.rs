@@ -1,31 +1,31 @@
-use std::{cell::RefCell, collections::VecDeque, ptr, rc::Rc};
+use std::{cell::{Ref, RefCell}, ptr, rc::Rc};
 
-use rand::random;
+use crate::{sweep_aabb, sweep_vertical_bounds, Arena, PaddleController, Side};
 
-use crate::{MAX_POS, MIN_POS};
+const MAX_BALL_COLLISIONS_PER_UPDATE: usize = 4;
 
 pub trait GameObject {
-    fn r#move(&mut self, x_distance: f32, y_distance: f32) {
+    fn r#move(&mut self, arena: &Arena, x_distance: f32, y_distance: f32) {
         let new_x = self.get_state().x + x_distance;
-        if new_x < MIN_POS {
-            self.get_state_mut().x = MIN_POS;
-        } else if new_x + self.get_state().width > MAX_POS {
-            self.get_state_mut().x = MAX_POS - self.get_state().width;
+        if new_x < 0.0 {
+            self.get_state_mut().x = 0.0;
+        } else if new_x + self.get_state().width > arena.width {
+            self.get_state_mut().x = arena.width - self.get_state().width;
         } else {
             self.get_state_mut().x = new_x;
         }
 
         let new_y = self.get_state().y + y_distance;
-        if new_y < MIN_POS {
-            self.get_state_mut().y = MIN_POS;
-        } else if new_y + self.get_state().height > MAX_POS {
-            self.get_state_mut().y = MAX_POS - self.get_state().height;
+        if new_y < 0.0 {
+            self.get_state_mut().y = 0.0;
+        } else if new_y + self.get_state().height > arena.height {
+            self.get_state_mut().y = arena.height - self.get_state().height;
         } else {
             self.get_state_mut().y = new_y;
         }
     }
 
-    fn update(&mut self, game_objects: &Vec<Rc<RefCell<dyn GameObject>>>);
+    fn update(&mut self, arena: &Arena, game_objects: &Vec<Rc<RefCell<dyn GameObject>>>);
 
     fn get_state(&self) -> &GameObjectState;
 
@@ -34,31 +34,53 @@ pub trait GameObject {
     fn as_paddle(&mut self) -> Option<&mut Paddle>;
 
     fn as_ball(&self) -> Option<&Ball>;
+
+    fn as_ball_mut(&mut self) -> Option<&mut Ball>;
 }
 
 pub struct Paddle {
     game_object_state: GameObjectState,
-    pub paddle_type: PaddleType,
-    pub move_commands: VecDeque<MoveCommand>
+    pub command: Option<MoveCommand>,
+    motion: PaddleMotion,
+    velocity: f32,
+    controller: Box<dyn PaddleController>
 }
 
 impl Paddle {
-    pub fn new(game_object_state: GameObjectState, paddle_type: PaddleType) -> Self {
-        Self { game_object_state, paddle_type, move_commands: VecDeque::new() }
+    pub fn new(game_object_state: GameObjectState, motion: PaddleMotion, controller: Box<dyn PaddleController>) -> Self {
+        Self { game_object_state, command: None, motion, velocity: 0.0, controller }
+    }
+
+    pub fn velocity(&self) -> f32 {
+        self.velocity
+    }
+
+    // Lets the controller know that this tick passed without an update
+    pub fn skip_update(&mut self) {
+        self.controller.skip_tick();
     }
 }
 
 impl GameObject for Paddle {
-    fn update(&mut self, game_objects: &Vec<Rc<RefCell<dyn GameObject>>>) {
-        if self.paddle_type == PaddleType::AI {
-            move_ai_paddle(self, game_objects);
-        }
-
-        while let Some(move_command) = self.move_commands.pop_front() {
-            match move_command {
-                MoveCommand::UP => self.r#move(0.0, 4.0),
-                MoveCommand::DOWN => self.r#move(0.0, -4.0),
-            }
+    fn update(&mut self, arena: &Arena, game_objects: &Vec<Rc<RefCell<dyn GameObject>>>) {
+        let command = self.command.take();
+        let axis = self.controller
+            .next_move(&self.game_object_state, &self.motion, command, arena, game_objects)
+            .clamp(-1.0, 1.0);
+
+        let target_velocity = axis * self.motion.max_speed;
+        let rate = if target_velocity.abs() > self.velocity.abs() && target_velocity * self.velocity >= 0.0 {
+            self.motion.acceleration
+        } else {
+            self.motion.deceleration
+        };
+        self.velocity += (target_velocity - self.velocity).clamp(-rate, rate);
+
+        let previous_y = self.game_object_state.y;
+        self.r#move(arena, 0.0, self.velocity);
+        if self.game_object_state.y == previous_y {
+            // Stopped by the bottom or top of the arena
+            self.velocity = 0.0;
         }
     }
 
@@ -77,83 +99,129 @@ impl GameObject for Paddle {
     fn as_ball(&self) -> Option<&Ball> {
         None
     }
+
+    fn as_ball_mut(&mut self) -> Option<&mut Ball> {
+        None
+    }
 }
 
-fn move_ai_paddle(ai_paddle: &mut Paddle, game_objects: &Vec<Rc<RefCell<dyn GameObject>>>) {
-    let ball = game_objects.iter().find(|game_object| {
-        if let Some(_) = game_object.borrow().as_ball() {
-            return true
+pub fn find_ball(game_objects: &[Rc<RefCell<dyn GameObject>>]) -> Ref<'_, dyn GameObject> {
+    game_objects.iter().find(|game_object| {
+        // The paddle being updated is already mutably borrowed
+        if let Ok(game_object) = game_object.try_borrow() {
+            return game_object.as_ball().is_some();
         }
         false
     })
     .expect("No ball found")
-    .borrow();
-    let ball_y_position = ball.get_state().y;
-
-    let distance_from_ball = (ai_paddle.game_object_state.y - ball_y_position).abs();
-    if distance_from_ball > ball.get_state().height * 1.5 {
-        if ai_paddle.game_object_state.y < ball_y_position {
-            ai_paddle.move_commands.push_back(MoveCommand::UP);
-        } else if ai_paddle.game_object_state.y > ball_y_position {
-            ai_paddle.move_commands.push_back(MoveCommand::DOWN);
-        }
-    }
+    .borrow()
 }
 
 pub struct Ball {
     game_object_state: GameObjectState,
     velocity_x: f32,
-    velocity_y: f32
+    velocity_y: f32,
+    speed: f32,
+    ball_speed: BallSpeed,
+    scored: Option<Side>
 }
 
 impl Ball {
-    pub fn new(game_object_state: GameObjectState, velocity_x: f32, velocity_y: f32) -> Self {
-        Self { game_object_state, velocity_x, velocity_y }
+    pub fn new(game_object_state: GameObjectState, ball_speed: BallSpeed) -> Self {
+        Self {
+            game_object_state,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            speed: ball_speed.base,
+            ball_speed,
+            scored: None
+        }
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    pub fn velocity(&self) -> (f32, f32) {
+        (self.velocity_x, self.velocity_y)
+    }
+
+    pub fn set_velocity(&mut self, velocity_x: f32, velocity_y: f32) {
+        self.velocity_x = velocity_x;
+        self.velocity_y = velocity_y;
+    }
+
+    pub fn take_point(&mut self) -> Option<Side> {
+        self.scored.take()
+    }
+
+    pub fn reset(&mut self, arena: &Arena) {
+        self.get_state_mut().x = arena.width / 2.0;
+        self.get_state_mut().y = arena.height / 2.0;
+        self.velocity_x = 0.0;
+        self.velocity_y = 0.0;
+    }
+
+    pub fn serve(&mut self, direction: Side) {
+        self.speed = self.ball_speed.base;
+        self.velocity_y = 0.0;
+        match direction {
+            Side::LEFT => self.velocity_x = -self.speed,
+            Side::RIGHT => self.velocity_x = self.speed,
+        }
     }
 }
 
 impl GameObject for Ball {
-    fn update(&mut self, game_objects: &Vec<Rc<RefCell<dyn GameObject>>>) {
-        if self.get_state().x <= MIN_POS || self.get_state().x + self.get_state().width >= MAX_POS {
-            self.get_state_mut().x = 50.0;
-            self.get_state_mut().y = 50.0;
-            self.velocity_y = 0.0;
-            if random() {
-                self.velocity_x = 1.0
+    fn update(&mut self, arena: &Arena, game_objects: &Vec<Rc<RefCell<dyn GameObject>>>) {
+        if self.get_state().x <= 0.0 || self.get_state().x + self.get_state().width >= arena.width {
+            if self.get_state().x <= 0.0 {
+                self.scored = Some(Side::RIGHT);
             } else {
-                self.velocity_x = -1.0
-            }
-            if random() {
-                self.velocity_x = 1.0
-            } else {
-                self.velocity_x = -1.0
+                self.scored = Some(Side::LEFT);
             }
+
+            self.reset(arena);
             return;
         }
 
-        for game_object in game_objects {
-            if ptr::addr_eq(self as &dyn GameObject, game_object.as_ptr()) {
-                continue;
+        let mut remaining_time = 1.0;
+        for _ in 0..MAX_BALL_COLLISIONS_PER_UPDATE {
+            let x_distance = self.velocity_x * remaining_time;
+            let y_distance = self.velocity_y * remaining_time;
+
+            let mut earliest_hit = sweep_vertical_bounds(self.get_state(), y_distance, 0.0, arena.height)
+                .map(|hit| (hit, None));
+            for game_object in game_objects {
+                if ptr::addr_eq(self as &dyn GameObject, game_object.as_ptr()) {
+                    continue;
+                }
+
+                let game_object = game_object.borrow();
+                let other_state = game_object.get_state();
+                if let Some(hit) = sweep_aabb(self.get_state(), x_distance, y_distance, other_state) {
+                    if earliest_hit.as_ref().is_none_or(|(earliest, _)| hit.time < earliest.time) {
+                        earliest_hit = Some((hit, Some((other_state.y, other_state.y + other_state.height))));
+                    }
+                }
             }
 
-            if self.get_state().x < game_object.borrow().get_state().x + game_object.borrow().get_state().width &&
-                self.get_state().x + self.get_state().width > game_object.borrow().get_state().x &&
-                self.get_state().y < game_object.borrow().get_state().y + game_object.borrow().get_state().height &&
-                self.get_state().y + self.get_state().height > game_object.borrow().get_state().y
-            {
-                self.velocity_y = linear_interpolate(
-                    self.get_state().y,
-                    (game_object.borrow().get_state().y, game_object.borrow().get_state().y + game_object.borrow().get_state().height)
-                );
-                self.velocity_x *= -1.0;
+            let Some((hit, paddle_range)) = earliest_hit else {
+                self.r#move(arena, x_distance, y_distance);
+                break;
+            };
+
+            self.r#move(arena, x_distance * hit.time, y_distance * hit.time);
+            match paddle_range {
+                Some(paddle_range) if hit.normal_x != 0.0 => {
+                    self.speed = (self.speed + self.ball_speed.acceleration).min(self.ball_speed.max);
+                    self.velocity_x = hit.normal_x * self.speed;
+                    self.velocity_y = linear_interpolate(self.get_state().y, paddle_range) * self.speed;
+                },
+                _ => self.velocity_y = hit.normal_y * self.velocity_y.abs()
             }
+            remaining_time *= 1.0 - hit.time;
         }
-
-        if self.get_state().y <= MIN_POS || self.get_state().y + self.get_state().height >= MAX_POS {
-            self.velocity_y *= -1.0;
-        }
-
-        self.r#move(self.velocity_x, self.velocity_y);
     }
 
     fn get_state(&self) -> &GameObjectState {
@@ -171,6 +239,10 @@ impl GameObject for Ball {
     fn as_ball(&self) -> Option<&Self> {
         Some(self)
     }
+
+    fn as_ball_mut(&mut self) -> Option<&mut Self> {
+        Some(self)
+    }
 }
 
 fn linear_interpolate(val: f32, source_range: (f32, f32)) -> f32 {
@@ -178,21 +250,48 @@ fn linear_interpolate(val: f32, source_range: (f32, f32)) -> f32 {
     target_range.0 + (val - source_range.0) * (target_range.1 - target_range.0) / (source_range.1 - source_range.0)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BallSpeed {
+    pub base: f32,
+    pub acceleration: f32,
+    pub max: f32,
+}
+
+impl Default for BallSpeed {
+    fn default() -> Self {
+        Self { base: 1.0, acceleration: 0.05, max: 3.0 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaddleMotion {
+    pub max_speed: f32,
+    pub acceleration: f32,
+    pub deceleration: f32,
+}
+
+impl Default for PaddleMotion {
+    fn default() -> Self {
+        Self { max_speed: 2.5, acceleration: 0.25, deceleration: 0.5 }
+    }
+}
+
 #[derive(Debug)]
 pub struct GameObjectState {
     pub height: f32,
     pub width: f32,
     pub x: f32,
     pub y: f32,
+    // RGB from 0.0 to 1.0, only used for drawing
+    pub color: [f32; 3],
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MoveCommand {
     UP,
-    DOWN
-}
-
-#[derive(PartialEq)]
-pub enum PaddleType {
-    PLAYER,
-    AI
+    DOWN,
+    // Move towards this y for the paddle's center, e.g. the mouse cursor's position
+    TARGET(f32),
+    // Analog input such as a gamepad stick, from -1.0 (down) to 1.0 (up)
+    AXIS(f32)
 }
\ No newline at end of file
@@ -0,0 +1,242 @@
+use crate::{MatchRules, Score, Side};
+
+const SERVE_DELAY_TICKS: u32 = 90;
+const SCORED_DELAY_TICKS: u32 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Phase {
+    TITLE,
+    SERVING,
+    PLAYING,
+    SCORED,
+    PAUSED,
+    FINISHED
+}
+
+#[derive(Debug)]
+pub struct GameState {
+    phase: Phase,
+    resume_phase: Phase,
+    phase_ticks: u32,
+    score: Score,
+    last_point: Option<Side>,
+    served: bool,
+}
+
+impl GameState {
+    pub fn new(rules: MatchRules) -> Self {
+        Self {
+            phase: Phase::TITLE,
+            resume_phase: Phase::TITLE,
+            phase_ticks: 0,
+            score: Score::new(rules),
+            last_point: None,
+            served: false,
+        }
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    pub fn score(&self) -> &Score {
+        &self.score
+    }
+
+    pub fn last_point(&self) -> Option<Side> {
+        self.last_point
+    }
+
+    pub fn take_serve(&mut self) -> bool {
+        std::mem::take(&mut self.served)
+    }
+
+    pub fn updates_paddles(&self) -> bool {
+        matches!(self.phase, Phase::SERVING | Phase::PLAYING | Phase::SCORED)
+    }
+
+    pub fn updates_ball(&self) -> bool {
+        self.phase == Phase::PLAYING
+    }
+
+    pub fn start(&mut self) {
+        if matches!(self.phase, Phase::TITLE | Phase::FINISHED) {
+            self.restart();
+        }
+    }
+
+    pub fn restart(&mut self) {
+        self.score.reset();
+        self.last_point = None;
+        self.set_phase(Phase::SERVING);
+    }
+
+    pub fn serve(&mut self) {
+        if self.phase == Phase::SERVING {
+            self.set_phase(Phase::PLAYING);
+        }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        match self.phase {
+            Phase::SERVING | Phase::PLAYING | Phase::SCORED => {
+                self.resume_phase = self.phase;
+                self.phase = Phase::PAUSED;
+            },
+            Phase::PAUSED => {
+                self.phase = self.resume_phase;
+            },
+            _ => ()
+        }
+    }
+
+    pub fn point_scored(&mut self, side: Side) {
+        if self.phase != Phase::PLAYING {
+            return;
+        }
+
+        self.score.award_point(side);
+        self.last_point = Some(side);
+
+        if self.score.is_game_over() {
+            self.set_phase(Phase::FINISHED);
+        } else {
+            self.set_phase(Phase::SCORED);
+        }
+    }
+
+    pub fn tick(&mut self) {
+        if self.phase == Phase::PAUSED {
+            return;
+        }
+
+        self.phase_ticks += 1;
+
+        match self.phase {
+            Phase::SERVING if self.phase_ticks >= SERVE_DELAY_TICKS => self.set_phase(Phase::PLAYING),
+            Phase::SCORED if self.phase_ticks >= SCORED_DELAY_TICKS => self.set_phase(Phase::SERVING),
+            _ => ()
+        }
+    }
+
+    fn set_phase(&mut self, phase: Phase) {
+        if phase == Phase::PLAYING {
+            self.served = true;
+        }
+        self.phase = phase;
+        self.phase_ticks = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticks(game_state: &mut GameState, count: u32) {
+        for _ in 0..count {
+            game_state.tick();
+        }
+    }
+
+    // Starts a match and waits for the ball to be served
+    fn playing(rules: MatchRules) -> GameState {
+        let mut game_state = GameState::new(rules);
+        game_state.start();
+        ticks(&mut game_state, SERVE_DELAY_TICKS);
+        assert_eq!(game_state.phase(), Phase::PLAYING);
+        assert!(game_state.take_serve());
+        game_state
+    }
+
+    #[test]
+    fn phases_of_a_point() {
+        let mut game_state = GameState::new(MatchRules::default());
+        assert_eq!(game_state.phase(), Phase::TITLE);
+
+        game_state.start();
+        assert_eq!(game_state.phase(), Phase::SERVING);
+        ticks(&mut game_state, SERVE_DELAY_TICKS - 1);
+        assert_eq!(game_state.phase(), Phase::SERVING);
+        game_state.tick();
+        assert_eq!(game_state.phase(), Phase::PLAYING);
+
+        game_state.point_scored(Side::RIGHT);
+        assert_eq!(game_state.phase(), Phase::SCORED);
+        assert_eq!(game_state.last_point(), Some(Side::RIGHT));
+        assert_eq!(game_state.score().points(Side::RIGHT), 1);
+
+        ticks(&mut game_state, SCORED_DELAY_TICKS);
+        assert_eq!(game_state.phase(), Phase::SERVING);
+    }
+
+    #[test]
+    fn serve_skips_the_delay() {
+        let mut game_state = GameState::new(MatchRules::default());
+        game_state.start();
+        game_state.serve();
+
+        assert_eq!(game_state.phase(), Phase::PLAYING);
+        assert!(game_state.take_serve());
+        assert!(!game_state.take_serve());
+    }
+
+    #[test]
+    fn pause_resumes_the_same_phase() {
+        for phase in [Phase::SERVING, Phase::PLAYING, Phase::SCORED] {
+            let mut game_state = playing(MatchRules::default());
+            match phase {
+                Phase::SERVING => {
+                    game_state.point_scored(Side::LEFT);
+                    ticks(&mut game_state, SCORED_DELAY_TICKS);
+                },
+                Phase::SCORED => game_state.point_scored(Side::LEFT),
+                _ => ()
+            }
+            assert_eq!(game_state.phase(), phase);
+
+            game_state.toggle_pause();
+            assert_eq!(game_state.phase(), Phase::PAUSED);
+            assert!(!game_state.updates_paddles() && !game_state.updates_ball());
+            // Time stands still while paused
+            ticks(&mut game_state, SERVE_DELAY_TICKS + SCORED_DELAY_TICKS);
+            assert_eq!(game_state.phase(), Phase::PAUSED);
+
+            game_state.toggle_pause();
+            assert_eq!(game_state.phase(), phase);
+        }
+    }
+
+    #[test]
+    fn finishes_on_game_over() {
+        let mut game_state = playing(MatchRules { points_to_win: 2, win_by: 1 });
+        game_state.point_scored(Side::LEFT);
+        ticks(&mut game_state, SCORED_DELAY_TICKS + SERVE_DELAY_TICKS);
+        game_state.point_scored(Side::LEFT);
+
+        assert_eq!(game_state.phase(), Phase::FINISHED);
+        assert_eq!(game_state.score().winner(), Some(Side::LEFT));
+
+        // Nothing moves the match on but starting over
+        game_state.toggle_pause();
+        ticks(&mut game_state, SCORED_DELAY_TICKS + SERVE_DELAY_TICKS);
+        game_state.point_scored(Side::RIGHT);
+        assert_eq!(game_state.phase(), Phase::FINISHED);
+        assert_eq!(game_state.score().points(Side::RIGHT), 0);
+
+        game_state.start();
+        assert_eq!(game_state.phase(), Phase::SERVING);
+        assert_eq!(game_state.score().points(Side::LEFT), 0);
+    }
+
+    #[test]
+    fn restart_resets_the_match() {
+        let mut game_state = playing(MatchRules::default());
+        game_state.point_scored(Side::LEFT);
+
+        game_state.restart();
+
+        assert_eq!(game_state.phase(), Phase::SERVING);
+        assert_eq!(game_state.last_point(), None);
+        assert_eq!((game_state.score().points(Side::LEFT), game_state.score().points(Side::RIGHT)), (0, 0));
+    }
+}
@@ -1,3 +1,15 @@
+mod ai;
+mod arena;
+mod collision;
+mod controller;
 mod game_object;
+mod game_state;
+mod score;
 
-pub use game_object::*;
\ No newline at end of file
+pub use ai::*;
+pub use arena::*;
+pub use collision::*;
+pub use controller::*;
+pub use game_object::*;
+pub use game_state::*;
+pub use score::*;
\ No newline at end of file
@@ -0,0 +1,132 @@
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Side {
+    LEFT,
+    RIGHT
+}
+
+impl Side {
+    pub fn opponent(&self) -> Side {
+        match self {
+            Side::LEFT => Side::RIGHT,
+            Side::RIGHT => Side::LEFT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MatchRules {
+    pub points_to_win: u32,
+    pub win_by: u32,
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        Self { points_to_win: 11, win_by: 2 }
+    }
+}
+
+#[derive(Debug)]
+pub struct Score {
+    rules: MatchRules,
+    left: u32,
+    right: u32,
+}
+
+impl Score {
+    pub fn new(rules: MatchRules) -> Self {
+        Self { rules, left: 0, right: 0 }
+    }
+
+    pub fn rules(&self) -> &MatchRules {
+        &self.rules
+    }
+
+    pub fn points(&self, side: Side) -> u32 {
+        match side {
+            Side::LEFT => self.left,
+            Side::RIGHT => self.right,
+        }
+    }
+
+    pub fn award_point(&mut self, side: Side) {
+        if self.is_game_over() {
+            return;
+        }
+
+        match side {
+            Side::LEFT => self.left += 1,
+            Side::RIGHT => self.right += 1,
+        }
+    }
+
+    pub fn winner(&self) -> Option<Side> {
+        let (leader, leading_points, trailing_points) = if self.left >= self.right {
+            (Side::LEFT, self.left, self.right)
+        } else {
+            (Side::RIGHT, self.right, self.left)
+        };
+
+        if leading_points >= self.rules.points_to_win && leading_points - trailing_points >= self.rules.win_by {
+            Some(leader)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.winner().is_some()
+    }
+
+    pub fn reset(&mut self) {
+        self.left = 0;
+        self.right = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(left: u32, right: u32) -> Score {
+        let mut score = Score::new(MatchRules::default());
+        for _ in 0..left {
+            score.award_point(Side::LEFT);
+        }
+        for _ in 0..right {
+            score.award_point(Side::RIGHT);
+        }
+        score
+    }
+
+    #[test]
+    fn first_to_points_to_win() {
+        assert_eq!(score(10, 9).winner(), None);
+        assert_eq!(score(11, 9).winner(), Some(Side::LEFT));
+        assert_eq!(score(3, 11).winner(), Some(Side::RIGHT));
+    }
+
+    #[test]
+    fn must_win_by_two() {
+        let mut score = score(10, 10);
+        score.award_point(Side::LEFT);
+        assert!(!score.is_game_over());
+
+        score.award_point(Side::LEFT);
+        assert_eq!(score.winner(), Some(Side::LEFT));
+        assert_eq!((score.points(Side::LEFT), score.points(Side::RIGHT)), (12, 10));
+    }
+
+    #[test]
+    fn no_points_after_game_over() {
+        let mut score = score(11, 0);
+
+        score.award_point(Side::RIGHT);
+        score.award_point(Side::LEFT);
+
+        assert_eq!((score.points(Side::LEFT), score.points(Side::RIGHT)), (11, 0));
+        assert_eq!(score.winner(), Some(Side::LEFT));
+    }
+}
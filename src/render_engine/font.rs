@@ -0,0 +1,108 @@
+use crate::Arena;
+
+const GLYPH_WIDTH: usize = 3;
+const GLYPH_HEIGHT: usize = 5;
+const GLYPH_ADVANCE: usize = GLYPH_WIDTH + 1;
+
+#[derive(Debug, Clone)]
+pub struct Label {
+    pub text: String,
+    pub x: f32,
+    pub y: f32,
+    pub size: f32,
+}
+
+impl Label {
+    pub fn new(text: &str, x: f32, y: f32, size: f32) -> Self {
+        Self { text: text.to_uppercase(), x, y, size }
+    }
+
+    pub fn centered(text: &str, center_x: f32, y: f32, size: f32) -> Self {
+        let mut label = Self::new(text, 0.0, y, size);
+        label.x = center_x - label.width() / 2.0;
+        label
+    }
+
+    pub fn centered_in_arena(text: &str, arena: &Arena, y: f32, size: f32) -> Self {
+        Self::centered(text, arena.width / 2.0, y, size)
+    }
+
+    pub fn width(&self) -> f32 {
+        let characters = self.text.chars().count();
+        if characters == 0 {
+            return 0.0;
+        }
+        ((characters * GLYPH_ADVANCE - 1) as f32) * self.size
+    }
+
+    pub fn height(&self) -> f32 {
+        GLYPH_HEIGHT as f32 * self.size
+    }
+
+    // Returns the lit cells of every glyph as (x, y, width, height) in game units,
+    // with (x, y) being the bottom left corner like a GameObjectState.
+    pub(crate) fn cells(&self) -> Vec<(f32, f32, f32, f32)> {
+        let mut cells = Vec::new();
+        for (i, character) in self.text.chars().enumerate() {
+            let glyph_x = self.x + (i * GLYPH_ADVANCE) as f32 * self.size;
+            for (row, bits) in glyph(character).iter().enumerate() {
+                let cell_y = self.y + (GLYPH_HEIGHT - 1 - row) as f32 * self.size;
+                for column in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - column)) != 0 {
+                        cells.push((glyph_x + column as f32 * self.size, cell_y, self.size, self.size));
+                    }
+                }
+            }
+        }
+        cells
+    }
+}
+
+// 3x5 glyphs, one row per byte from top to bottom, most significant of the 3 bits on the left
+fn glyph(character: char) -> [u8; GLYPH_HEIGHT] {
+    match character {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        '!' => [0b010, 0b010, 0b010, 0b000, 0b010],
+        '?' => [0b111, 0b001, 0b011, 0b000, 0b010],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        _ => [0b000; GLYPH_HEIGHT],
+    }
+}
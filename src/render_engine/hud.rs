@@ -0,0 +1,66 @@
+use crate::{Arena, GameState, Phase, Side};
+
+use super::Label;
+
+// Label sizes are given for an arena of this width and height and scale with the smaller side
+const REFERENCE_ARENA_SIZE: f32 = 100.0;
+
+pub fn hud_labels(game_state: &GameState, arena: &Arena) -> Vec<Label> {
+    let mut labels = Vec::new();
+    let height = arena.height;
+    let scale = arena.width.min(arena.height) / REFERENCE_ARENA_SIZE;
+
+    if game_state.phase() == Phase::TITLE {
+        labels.push(Label::centered_in_arena("PONG", arena, height * 0.55, 3.0 * scale));
+        labels.push(Label::centered_in_arena("PRESS ENTER", arena, height * 0.35, 1.0 * scale));
+        return labels;
+    }
+
+    let score = game_state.score();
+    labels.push(Label::centered(&score.points(Side::LEFT).to_string(), arena.width * 0.25, height * 0.85, 2.0 * scale));
+    labels.push(Label::centered(&score.points(Side::RIGHT).to_string(), arena.width * 0.75, height * 0.85, 2.0 * scale));
+
+    match game_state.phase() {
+        Phase::PAUSED => {
+            labels.push(Label::centered_in_arena("PAUSED", arena, height * 0.55, 2.0 * scale));
+        },
+        Phase::FINISHED => {
+            let winner = match score.winner() {
+                Some(Side::LEFT) => "PLAYER 1 WINS",
+                Some(Side::RIGHT) => "PLAYER 2 WINS",
+                None => "GAME OVER",
+            };
+            labels.push(Label::centered_in_arena(winner, arena, height * 0.55, 1.5 * scale));
+            labels.push(Label::centered_in_arena("PRESS ENTER", arena, height * 0.35, 1.0 * scale));
+        },
+        _ => ()
+    }
+
+    labels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatchRules;
+
+    fn assert_inside(labels: &[Label], arena: &Arena) {
+        for label in labels {
+            assert!(label.x >= 0.0 && label.x + label.width() <= arena.width, "{} is too wide", label.text);
+            assert!(label.y >= 0.0 && label.y + label.height() <= arena.height, "{} is too high", label.text);
+        }
+    }
+
+    #[test]
+    fn labels_fit_small_arenas() {
+        let arena = Arena { width: 30.0, height: 20.0 };
+        let mut game_state = GameState::new(MatchRules { points_to_win: 1, win_by: 1 });
+        assert_inside(&hud_labels(&game_state, &arena), &arena);
+
+        game_state.start();
+        game_state.serve();
+        game_state.point_scored(Side::LEFT);
+        assert_eq!(game_state.phase(), Phase::FINISHED);
+        assert_inside(&hud_labels(&game_state, &arena), &arena);
+    }
+}
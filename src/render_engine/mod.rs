@@ -1,3 +1,29 @@
+mod font;
+mod frame;
+mod hud;
+#[cfg(feature = "vulkan")]
+mod offscreen;
+#[cfg(feature = "vulkan")]
 mod render_engine;
+mod renderer;
+mod software;
+#[cfg(feature = "window")]
+mod software_window;
+#[cfg(feature = "terminal")]
+mod terminal;
+mod theme;
 
-pub use render_engine::RenderEngine;
\ No newline at end of file
+pub use font::Label;
+pub use frame::Frame;
+pub use hud::hud_labels;
+#[cfg(feature = "vulkan")]
+pub use offscreen::OffscreenRenderer;
+#[cfg(feature = "vulkan")]
+pub use render_engine::RenderEngine;
+pub use renderer::{Renderer, RendererKind, WindowSettings};
+pub use software::SoftwareRenderer;
+#[cfg(feature = "window")]
+pub use software_window::SoftwareWindowRenderer;
+#[cfg(feature = "terminal")]
+pub use terminal::TerminalRenderer;
+pub use theme::{Theme, ThemeName};
@@ -0,0 +1,167 @@
+use std::{cell::RefCell, rc::Rc, sync::Arc};
+
+use vulkano::{buffer::{Buffer, BufferCreateInfo, BufferUsage, Subbuffer}, command_buffer::{allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, CommandBufferUsage, CopyImageToBufferInfo}, device::{Device, DeviceCreateInfo, DeviceExtensions, Queue, QueueCreateInfo}, format::Format, image::{view::ImageView, Image, ImageCreateInfo, ImageType, ImageUsage}, instance::{Instance, InstanceCreateFlags, InstanceCreateInfo}, memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator}, pipeline::{graphics::viewport::Viewport, GraphicsPipeline}, render_pass::{Framebuffer, FramebufferCreateInfo}, sync::{self, GpuFuture}, VulkanLibrary};
+use winit::dpi::PhysicalSize;
+
+use crate::{Arena, GameObject};
+
+use super::{render_engine::{fragment_shader, get_pipeline, get_render_pass, get_squares, letterbox_viewport, record_frame, select_physical_device, vertex_shader, InstanceBuffer, QuadMesh}, Frame, Label, Theme};
+
+const FRAME_FORMAT: Format = Format::R8G8B8A8_UNORM;
+
+// Renders into a Vulkan image instead of a window, so it also works without a display, e.g.
+// on a CPU implementation like lavapipe
+pub struct OffscreenRenderer {
+    device: Arc<Device>,
+    queue: Arc<Queue>,
+    command_buffer_allocator: Arc<StandardCommandBufferAllocator>,
+    pipeline: Arc<GraphicsPipeline>,
+    viewport: Viewport,
+    image: Arc<Image>,
+    framebuffer: Arc<Framebuffer>,
+    readback_buffer: Subbuffer<[u8]>,
+    quad: QuadMesh,
+    instances: InstanceBuffer,
+    width: u32,
+    height: u32,
+    arena: Arena,
+    theme: Theme,
+}
+
+impl OffscreenRenderer {
+    pub fn new(width: u32, height: u32, arena: Arena, theme: Theme) -> OffscreenRenderer {
+        let library = VulkanLibrary::new().expect("No Vulkan library found");
+        let instance = Instance::new(
+            library,
+            InstanceCreateInfo {
+                flags: InstanceCreateFlags::ENUMERATE_PORTABILITY,
+                ..Default::default()
+            }
+        )
+        .expect("Failed to create instance");
+
+        let device_extensions = DeviceExtensions::empty();
+        let (physical_device, queue_family_index) = select_physical_device(&instance, None, &device_extensions);
+
+        let (device, mut queues) = Device::new(
+            physical_device,
+            DeviceCreateInfo {
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index,
+                    ..Default::default()
+                }],
+                enabled_extensions: device_extensions,
+                ..Default::default()
+            }
+        )
+        .expect("Failed to create device");
+
+        let queue = queues.next().unwrap();
+
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(device.clone(), Default::default()));
+
+        let image = Image::new(
+            memory_allocator.clone(),
+            ImageCreateInfo {
+                image_type: ImageType::Dim2d,
+                format: FRAME_FORMAT,
+                extent: [width, height, 1],
+                usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::TRANSFER_SRC,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
+                ..Default::default()
+            }
+        )
+        .expect("Failed to create offscreen image");
+
+        let readback_buffer = Buffer::from_iter(
+            memory_allocator.clone(),
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                ..Default::default()
+            },
+            (0..width * height * 4).map(|_| 0u8)
+        )
+        .expect("Failed to create readback buffer");
+
+        let render_pass = get_render_pass(device.clone(), FRAME_FORMAT);
+        let framebuffer = Framebuffer::new(
+            render_pass.clone(),
+            FramebufferCreateInfo {
+                attachments: vec![ImageView::new_default(image.clone()).unwrap()],
+                ..Default::default()
+            }
+        )
+        .unwrap();
+
+        let viewport = letterbox_viewport(PhysicalSize::new(width, height), &arena);
+        let vertex_shader = vertex_shader::load(device.clone()).expect("Failed to load vertex shader");
+        let fragment_shader = fragment_shader::load(device.clone()).expect("Failed to load fragment shader");
+        let pipeline = get_pipeline(device.clone(), vertex_shader, fragment_shader, render_pass, viewport.clone());
+
+        let quad = QuadMesh::new(memory_allocator.clone());
+        let instances = InstanceBuffer::new(memory_allocator);
+
+        Self {
+            device,
+            queue,
+            command_buffer_allocator,
+            pipeline,
+            viewport,
+            image,
+            framebuffer,
+            readback_buffer,
+            quad,
+            instances,
+            width,
+            height,
+            arena,
+            theme
+        }
+    }
+
+    pub fn render(&mut self, game_objects: &[Rc<RefCell<dyn GameObject>>], labels: &[Label]) -> Frame {
+        self.instances.upload(&get_squares(&self.arena, game_objects, labels, self.theme.text));
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            self.command_buffer_allocator.as_ref(),
+            self.queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit
+        )
+        .unwrap();
+
+        record_frame(
+            &mut builder,
+            &self.pipeline,
+            &self.framebuffer,
+            &self.viewport,
+            &self.theme,
+            &self.quad,
+            &self.instances
+        );
+        builder
+            .copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(self.image.clone(), self.readback_buffer.clone()))
+            .unwrap();
+
+        sync::now(self.device.clone())
+            .then_execute(self.queue.clone(), builder.build().unwrap())
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .expect("Failed to flush offscreen frame")
+            .wait(None)
+            .unwrap();
+
+        Frame {
+            width: self.width,
+            height: self.height,
+            pixels: self.readback_buffer.read().unwrap().to_vec()
+        }
+    }
+}
@@ -1,15 +1,19 @@
 use std::{cell::RefCell, rc::Rc, sync::Arc};
 
-use vulkano::{buffer::{Buffer, BufferContents, BufferCreateInfo, BufferUsage, Subbuffer}, command_buffer::{allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, CommandBufferUsage, PrimaryAutoCommandBuffer, RenderPassBeginInfo, SubpassBeginInfo, SubpassContents, SubpassEndInfo}, device::{physical::{self, PhysicalDevice}, Device, DeviceCreateInfo, DeviceExtensions, Queue, QueueCreateInfo, QueueFlags}, image::{view::ImageView, Image, ImageUsage}, instance::{Instance, InstanceCreateFlags, InstanceCreateInfo}, memory::allocator::{AllocationCreateInfo, FreeListAllocator, GenericMemoryAllocator, MemoryTypeFilter, StandardMemoryAllocator}, pipeline::{graphics::{color_blend::{ColorBlendAttachmentState, ColorBlendState}, input_assembly::InputAssemblyState, multisample::MultisampleState, rasterization::RasterizationState, vertex_input::{Vertex, VertexDefinition}, viewport::{Viewport, ViewportState}, GraphicsPipelineCreateInfo}, layout::PipelineDescriptorSetLayoutCreateInfo, GraphicsPipeline, PipelineLayout, PipelineShaderStageCreateInfo}, render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass, Subpass}, shader::ShaderModule, swapchain::{self, Surface, Swapchain, SwapchainCreateInfo, SwapchainPresentInfo}, sync::{self, GpuFuture}, Validated, VulkanError, VulkanLibrary};
-use winit::{event_loop::EventLoop, window::{Window, WindowBuilder}};
+use vulkano::{buffer::{Buffer, BufferContents, BufferCreateInfo, BufferUsage, Subbuffer}, command_buffer::{allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, ClearAttachment, ClearRect, CommandBufferUsage, PrimaryAutoCommandBuffer, RenderPassBeginInfo, SubpassBeginInfo, SubpassContents, SubpassEndInfo}, device::{physical::{self, PhysicalDevice}, Device, DeviceCreateInfo, DeviceExtensions, Queue, QueueCreateInfo, QueueFlags}, format::{ClearColorValue, Format}, image::{view::ImageView, Image, ImageUsage}, instance::{Instance, InstanceCreateFlags, InstanceCreateInfo}, memory::allocator::{AllocationCreateInfo, FreeListAllocator, GenericMemoryAllocator, MemoryTypeFilter, StandardMemoryAllocator}, pipeline::{graphics::{color_blend::{ColorBlendAttachmentState, ColorBlendState}, input_assembly::InputAssemblyState, multisample::MultisampleState, rasterization::RasterizationState, vertex_input::{Vertex, VertexDefinition}, viewport::{Viewport, ViewportState}, GraphicsPipelineCreateInfo}, layout::PipelineDescriptorSetLayoutCreateInfo, GraphicsPipeline, PipelineLayout, PipelineShaderStageCreateInfo}, render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass, Subpass}, shader::ShaderModule, swapchain::{self, Surface, Swapchain, SwapchainCreateInfo, SwapchainPresentInfo}, sync::{self, GpuFuture}, Validated, VulkanError, VulkanLibrary};
+use winit::{dpi::{PhysicalPosition, PhysicalSize}, event_loop::EventLoop, window::Window};
 
-use crate::GameObject;
+use crate::{Arena, GameObject};
+
+use super::{renderer::{build_window, game_rects, ArenaViewport}, Label, Renderer, Theme, WindowSettings};
+
+// Enough for the paddles, the ball and the HUD without growing
+const INITIAL_INSTANCE_CAPACITY: u64 = 256;
 
 pub struct RenderEngine {
     device: Arc<Device>,
     queue: Arc<Queue>,
     swapchain: Arc<Swapchain>,
-    memory_allocator: Arc<GenericMemoryAllocator<FreeListAllocator>>, // TODO: See if free list allocator crashes after a while because of memory fragmentation,
     command_buffer_allocator: Arc<StandardCommandBufferAllocator>,
     pipeline: Arc<GraphicsPipeline>,
     window: Arc<Window>,
@@ -19,10 +23,17 @@ pub struct RenderEngine {
     render_pass: Arc<RenderPass>,
     framebuffers: Vec<Arc<Framebuffer>>,
     recreate_swapchain: bool,
+    quad: QuadMesh,
+    instances: InstanceBuffer,
+    command_buffers: Vec<Arc<PrimaryAutoCommandBuffer>>,
+    // How many instances the command buffers draw, None when they have to be recorded again
+    recorded_instances: Option<u32>,
+    arena: Arena,
+    theme: Theme,
 }
 
 impl RenderEngine {
-    pub fn new(event_loop: &EventLoop<()>) -> RenderEngine {
+    pub fn new(event_loop: &EventLoop<()>, window_settings: WindowSettings, arena: Arena) -> RenderEngine {
         let library = VulkanLibrary::new().expect("No Vulkan library found");
         let required_extensions = Surface::required_extensions(event_loop);
         let instance = Instance::new(
@@ -35,7 +46,7 @@ impl RenderEngine {
         )
         .expect("Failed to create instance");
 
-        let window = Arc::new(WindowBuilder::new().build(event_loop).expect("Failed to create window"));
+        let window = Arc::new(build_window(event_loop, window_settings));
         let surface = Surface::from_window(instance.clone(), window.clone()).expect("Failed to create surface");
 
         let device_extensions = DeviceExtensions {
@@ -45,7 +56,7 @@ impl RenderEngine {
 
         let (physical_device, queue_family_index) = select_physical_device(
             &instance,
-            &surface,
+            Some(&surface),
             &device_extensions
         );
 
@@ -89,20 +100,18 @@ impl RenderEngine {
         )
         .unwrap();
 
-        let render_pass = get_render_pass(device.clone(), &swapchain);
+        let render_pass = get_render_pass(device.clone(), swapchain.image_format());
         let framebuffers = get_framebuffers(&images, &render_pass);
 
-        let viewport = Viewport {
-            offset: [0.0, 0.0],
-            extent: window.inner_size().into(),
-            depth_range: 0.0..=1.0
-        };
+        let viewport = letterbox_viewport(window.inner_size(), &arena);
 
         let vertex_shader = vertex_shader::load(device.clone()).expect("Failed to load vertex shader");
         let fragment_shader = fragment_shader::load(device.clone()).expect("Failed to load fragment shader");
 
         let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
         let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(device.clone(), Default::default()));
+        let quad = QuadMesh::new(memory_allocator.clone());
+        let instances = InstanceBuffer::new(memory_allocator);
 
         let pipeline = get_pipeline(
             device.clone(), 
@@ -116,7 +125,6 @@ impl RenderEngine {
             device,
             queue,
             swapchain,
-            memory_allocator,
             command_buffer_allocator,
             pipeline,
             window,
@@ -125,41 +133,30 @@ impl RenderEngine {
             fragment_shader,
             render_pass,
             framebuffers,
-            recreate_swapchain: false
+            recreate_swapchain: false,
+            quad,
+            instances,
+            command_buffers: Vec::new(),
+            recorded_instances: None,
+            arena,
+            theme: window_settings.theme
         }
     }
 
-    pub fn draw(&mut self, game_objects: &Vec<Rc<RefCell<dyn GameObject>>>) {
-        let squares:Vec<Square> = game_objects.iter()
-            .map(|game_object| {
-                Square { 
-                    x: game_unit_to_render_unit(game_object.borrow().get_state().x) - 1.0, 
-                    y: -1.0 * (game_unit_to_render_unit(game_object.borrow().get_state().y) - 1.0),
-                    width: game_unit_to_render_unit(game_object.borrow().get_state().width),
-                    height: game_unit_to_render_unit(game_object.borrow().get_state().height)
-                }
-            })
-            .collect();
-
-        self.render(squares);
-    }
-
-    fn render(&mut self, squares: Vec<Square>) {
+    // Every frame is waited for before returning, so the instance buffer is free to be
+    // overwritten by the next one
+    fn render(&mut self, squares: &[Square]) {
         if self.recreate_swapchain {
             self.recreate_swapchain();
         }
 
-        let (vertex_buffer, index_buffer) = get_square_buffers(squares, self.memory_allocator.clone());
-
-        // TODO: no need to recreate index buffer
-        let command_buffers = get_command_buffers(
-            &self.command_buffer_allocator, 
-            &self.queue,
-            &self.pipeline, 
-            &self.framebuffers, 
-            &vertex_buffer,
-            &index_buffer
-        );
+        if self.instances.upload(squares) {
+            self.recorded_instances = None;
+        }
+        if self.recorded_instances != Some(self.instances.len) {
+            self.command_buffers = self.record_command_buffers();
+            self.recorded_instances = Some(self.instances.len);
+        }
 
         let (image_i, suboptimal, acquire_future) =
             match swapchain::acquire_next_image(self.swapchain.clone(), None)
@@ -179,7 +176,7 @@ impl RenderEngine {
 
         let execution = sync::now(self.device.clone())
             .join(acquire_future)
-            .then_execute(self.queue.clone(), command_buffers[image_i as usize].clone())
+            .then_execute(self.queue.clone(), self.command_buffers[image_i as usize].clone())
             .unwrap()
             .then_swapchain_present(
                 self.queue.clone(), 
@@ -200,21 +197,6 @@ impl RenderEngine {
         }
     }
 
-    pub fn on_window_resized(&mut self) {
-        self.recreate_swapchain();
-
-        let new_dimensions = self.window.inner_size();
-
-        self.viewport.extent = new_dimensions.into();
-        self.pipeline = get_pipeline(
-            self.device.clone(), 
-            self.vertex_shader.clone(),
-            self.fragment_shader.clone(), 
-            self.render_pass.clone(), 
-            self.viewport.clone()
-        );
-    }
-
     fn recreate_swapchain(&mut self) {
         let new_dimensions = self.window.inner_size();
 
@@ -226,18 +208,63 @@ impl RenderEngine {
             .expect("Failed to recreate swapchain");
 
         self.swapchain = new_swapchain;
-        self.framebuffers = get_framebuffers(&new_images, &self.render_pass.clone())
+        self.framebuffers = get_framebuffers(&new_images, &self.render_pass.clone());
+        self.recorded_instances = None;
+    }
+
+    // One command buffer per framebuffer, which can be submitted again for every frame until the
+    // number of instances, the instance buffer, the framebuffers or the pipeline change
+    fn record_command_buffers(&self) -> Vec<Arc<PrimaryAutoCommandBuffer>> {
+        self.framebuffers
+            .iter()
+            .map(|framebuffer| {
+                let mut builder = AutoCommandBufferBuilder::primary(
+                    self.command_buffer_allocator.as_ref(),
+                    self.queue.queue_family_index(),
+                    CommandBufferUsage::MultipleSubmit
+                )
+                .unwrap();
+
+                record_frame(&mut builder, &self.pipeline, framebuffer, &self.viewport, &self.theme, &self.quad, &self.instances);
+
+                builder.build().unwrap()
+            })
+            .collect()
+    }
+}
+
+impl Renderer for RenderEngine {
+    fn draw(&mut self, game_objects: &[Rc<RefCell<dyn GameObject>>], labels: &[Label]) {
+        self.render(&get_squares(&self.arena, game_objects, labels, self.theme.text));
+    }
+
+    fn on_window_resized(&mut self) {
+        self.recreate_swapchain();
+
+        self.viewport = letterbox_viewport(self.window.inner_size(), &self.arena);
+        self.pipeline = get_pipeline(
+            self.device.clone(), 
+            self.vertex_shader.clone(),
+            self.fragment_shader.clone(), 
+            self.render_pass.clone(), 
+            self.viewport.clone()
+        );
+        self.recorded_instances = None;
+    }
+
+    fn window_to_game_units(&self, position: PhysicalPosition<f64>) -> (f32, f32) {
+        ArenaViewport::letterbox(self.window.inner_size(), &self.arena).game_units_at(&self.arena, position)
     }
 }
 
 #[derive(BufferContents, Vertex)]
 #[repr(C)]
-struct MyVertex {
+pub(super) struct MyVertex {
     #[format(R32G32_SFLOAT)]
     position: [f32; 2]
 }
 
-mod vertex_shader {
+pub(super) mod vertex_shader {
     vulkano_shaders::shader! {
         ty: "vertex",
         src: r"
@@ -245,31 +272,42 @@ mod vertex_shader {
 
             layout(location = 0) in vec2 position;
 
+            // Per instance
+            layout(location = 1) in vec2 offset;
+            layout(location = 2) in vec2 size;
+            layout(location = 3) in vec3 color;
+
+            layout(location = 0) out vec3 v_color;
+
             void main() {
-                gl_Position = vec4(position, 0.0, 1.0);
+                gl_Position = vec4(offset + position * size, 0.0, 1.0);
+                v_color = color;
             }
         ",
     }
 }
 
-mod fragment_shader {
+pub(super) mod fragment_shader {
     vulkano_shaders::shader! {
         ty: "fragment",
         src: r"
             #version 460
 
+            layout(location = 0) in vec3 v_color;
+
             layout(location = 0) out vec4 f_color;
 
             void main() {
-                f_color = vec4(1.0, 1.0, 1.0, 1.0);
+                f_color = vec4(v_color, 1.0);
             }
         ",
     }
 }
 
-fn select_physical_device(
+// Any graphics queue will do without a surface, e.g. when rendering offscreen
+pub(super) fn select_physical_device(
     instance: &Arc<Instance>,
-    surface: &Arc<Surface>,
+    surface: Option<&Arc<Surface>>,
     device_extensions: &DeviceExtensions,
 ) -> (Arc<PhysicalDevice>, u32) {
     instance
@@ -281,7 +319,8 @@ fn select_physical_device(
                 .iter()
                 .enumerate()
                 .position(|(i, q)| {
-                    q.queue_flags.contains(QueueFlags::GRAPHICS) && device.surface_support(i as u32, &surface).unwrap()
+                    q.queue_flags.contains(QueueFlags::GRAPHICS)
+                        && surface.is_none_or(|surface| device.surface_support(i as u32, surface).unwrap())
                 })
                 .map(|q| (device, q as u32))
         })
@@ -295,12 +334,12 @@ fn select_physical_device(
         .expect("No physical device found")
 }
 
-fn get_render_pass(device: Arc<Device>, swapchain: &Arc<Swapchain>) -> Arc<RenderPass> {
+pub(super) fn get_render_pass(device: Arc<Device>, format: Format) -> Arc<RenderPass> {
     vulkano::single_pass_renderpass!(
         device,
         attachments: {
             color: {
-                format: swapchain.image_format(),
+                format,
                 samples: 1,
                 load_op: Clear,
                 store_op: Store   
@@ -314,7 +353,7 @@ fn get_render_pass(device: Arc<Device>, swapchain: &Arc<Swapchain>) -> Arc<Rende
     .unwrap()
 }
 
-fn get_framebuffers(images: &[Arc<Image>], render_pass: &Arc<RenderPass>) -> Vec<Arc<Framebuffer>> {
+pub(super) fn get_framebuffers(images: &[Arc<Image>], render_pass: &Arc<RenderPass>) -> Vec<Arc<Framebuffer>> {
     images
         .iter()
         .map(|image| {
@@ -331,7 +370,7 @@ fn get_framebuffers(images: &[Arc<Image>], render_pass: &Arc<RenderPass>) -> Vec
         .collect()
 }
 
-fn get_pipeline(
+pub(super) fn get_pipeline(
     device: Arc<Device>,
     vertex_shader: Arc<ShaderModule>,
     fragment_shader: Arc<ShaderModule>,
@@ -341,7 +380,7 @@ fn get_pipeline(
     let vertex_shader = vertex_shader.entry_point("main").unwrap();
     let fragment_shader = fragment_shader.entry_point("main").unwrap();
 
-    let vertex_input_state = MyVertex::per_vertex()
+    let vertex_input_state = [MyVertex::per_vertex(), Square::per_instance()]
         .definition(&vertex_shader.info().input_interface)
         .unwrap();
 
@@ -384,122 +423,180 @@ fn get_pipeline(
     .unwrap()
 }
 
-fn get_command_buffers(
-    command_buffer_allocator: &StandardCommandBufferAllocator,
-    queue: &Arc<Queue>,
+pub(super) fn record_frame(
+    builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
     pipeline: &Arc<GraphicsPipeline>,
-    framebuffers: &[Arc<Framebuffer>],
-    vertex_buffer: &Subbuffer<[MyVertex]>,
-    index_buffer: &Subbuffer<[u32]>
-) -> Vec<Arc<PrimaryAutoCommandBuffer>> {
-    framebuffers
-        .iter()
-        .map(|framebuffer| {
-            let mut builder = AutoCommandBufferBuilder::primary(
-                command_buffer_allocator, 
-                queue.queue_family_index(), 
-                CommandBufferUsage::MultipleSubmit
-            )
-            .unwrap();
-
-            builder
-                .begin_render_pass(
-                    RenderPassBeginInfo {
-                        clear_values: vec![Some([0.0, 0.0, 0.0, 1.0].into())],
-                        ..RenderPassBeginInfo::framebuffer(framebuffer.clone())
-                    },
-                    SubpassBeginInfo {
-                        contents: SubpassContents::Inline,
-                        ..Default::default()
-                    }
-                )
-                .unwrap()
-                .bind_pipeline_graphics(pipeline.clone())
-                .unwrap()
-                .bind_vertex_buffers(0, vertex_buffer.clone())
-                .unwrap()
-                .bind_index_buffer(index_buffer.clone())
-                .unwrap()
-                .draw_indexed(index_buffer.len() as u32, 1, 0, 0, 0)
-                .unwrap()
-                .end_render_pass(SubpassEndInfo::default())
-                .unwrap();
+    framebuffer: &Arc<Framebuffer>,
+    viewport: &Viewport,
+    theme: &Theme,
+    quad: &QuadMesh,
+    instances: &InstanceBuffer
+) {
+    builder
+        .begin_render_pass(
+            RenderPassBeginInfo {
+                clear_values: vec![Some([theme.border[0], theme.border[1], theme.border[2], 1.0].into())],
+                ..RenderPassBeginInfo::framebuffer(framebuffer.clone())
+            },
+            SubpassBeginInfo {
+                contents: SubpassContents::Inline,
+                ..Default::default()
+            }
+        )
+        .unwrap()
+        .clear_attachments(
+            [ClearAttachment::Color {
+                color_attachment: 0,
+                clear_value: ClearColorValue::Float([theme.arena[0], theme.arena[1], theme.arena[2], 1.0])
+            }].into_iter().collect(),
+            [ClearRect {
+                offset: [viewport.offset[0] as u32, viewport.offset[1] as u32],
+                extent: [viewport.extent[0] as u32, viewport.extent[1] as u32],
+                array_layers: 0..1
+            }].into_iter().collect()
+        )
+        .unwrap()
+        .bind_pipeline_graphics(pipeline.clone())
+        .unwrap()
+        .bind_vertex_buffers(0, (quad.vertices.clone(), instances.buffer.clone()))
+        .unwrap()
+        .bind_index_buffer(quad.indices.clone())
+        .unwrap()
+        .draw_indexed(quad.indices.len() as u32, instances.len, 0, 0, 0)
+        .unwrap()
+        .end_render_pass(SubpassEndInfo::default())
+        .unwrap();
+}
 
-            builder.build().unwrap()
-        })
+pub(super) fn get_squares(arena: &Arena, game_objects: &[Rc<RefCell<dyn GameObject>>], labels: &[Label], text_color: [f32; 3]) -> Vec<Square> {
+    game_rects(game_objects, labels, text_color)
+        .into_iter()
+        .map(|rect| Square::from_game_units(arena, rect.x, rect.y, rect.width, rect.height, rect.color))
         .collect()
 }
 
-fn get_square_buffers(squares: Vec<Square>, memory_allocator: Arc<GenericMemoryAllocator<FreeListAllocator>>) -> (Subbuffer<[MyVertex]>, Subbuffer<[u32]>) {
-    let mut vertices: Vec<MyVertex> = Vec::new();
-    let mut indices: Vec<u32> = Vec::new();
-    for (i, square) in squares.iter().enumerate() {
-        let base_index= (i * 4) as u32;
+// A unit square that every Square instance scales and moves into place
+pub(super) struct QuadMesh {
+    vertices: Subbuffer<[MyVertex]>,
+    indices: Subbuffer<[u32]>,
+}
 
-        let top_left = MyVertex {
-            position: [square.x, square.y]
-        };
-        let top_right = MyVertex {
-            position: [square.x + square.width, square.y]
-        };
-        let bottom_left = MyVertex {
-            position: [square.x, square.y - square.height]
-        };
-        let bottom_right = MyVertex {
-            position: [square.x + square.width, square.y - square.height]
-        };
+impl QuadMesh {
+    pub(super) fn new(memory_allocator: Arc<GenericMemoryAllocator<FreeListAllocator>>) -> Self {
+        let vertices = Buffer::from_iter(
+            memory_allocator.clone(),
+            BufferCreateInfo {
+                usage: BufferUsage::VERTEX_BUFFER,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+            // Top left, top right, bottom left and bottom right
+            [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0]].map(|position| MyVertex { position })
+        )
+        .unwrap();
 
-        vertices.push(top_left);
-        vertices.push(top_right);
-        vertices.push(bottom_left);
-        vertices.push(bottom_right);
+        let indices = Buffer::from_iter(
+            memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::INDEX_BUFFER,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+            [0u32, 2, 3, 0, 1, 3]
+        )
+        .unwrap();
 
-        indices.extend_from_slice(&[
-            base_index + 0, base_index + 2, base_index + 3,
-            base_index + 0, base_index + 1, base_index + 3,
-        ]);
+        Self { vertices, indices }
     }
+}
 
-    let vertex_buffer = Buffer::from_iter(
-        memory_allocator.clone(), 
-        BufferCreateInfo {
-            usage: BufferUsage::VERTEX_BUFFER,
-            ..Default::default()
-        }, 
-        AllocationCreateInfo {
-            memory_type_filter: MemoryTypeFilter::PREFER_DEVICE | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
-            ..Default::default()
-        }, 
-        vertices
-    )
-    .unwrap();
+// The squares of the current frame, kept in one buffer that is reused between frames and only
+// reallocated, at twice the size needed, when a frame has more squares than it can hold
+pub(super) struct InstanceBuffer {
+    memory_allocator: Arc<GenericMemoryAllocator<FreeListAllocator>>,
+    buffer: Subbuffer<[Square]>,
+    len: u32,
+}
+
+impl InstanceBuffer {
+    pub(super) fn new(memory_allocator: Arc<GenericMemoryAllocator<FreeListAllocator>>) -> Self {
+        let buffer = allocate_instances(&memory_allocator, INITIAL_INSTANCE_CAPACITY);
+        Self { memory_allocator, buffer, len: 0 }
+    }
+
+    // Returns whether the buffer was reallocated, which command buffers recorded before do not
+    // see. The GPU must be done with the previous frame
+    pub(super) fn upload(&mut self, squares: &[Square]) -> bool {
+        let reallocated = squares.len() as u64 > self.buffer.len();
+        if reallocated {
+            self.buffer = allocate_instances(&self.memory_allocator, squares.len() as u64 * 2);
+        }
+
+        self.buffer.write().expect("Instance buffer is still in use")[..squares.len()].copy_from_slice(squares);
+        self.len = squares.len() as u32;
+        reallocated
+    }
+}
 
-    let index_buffer = Buffer::from_iter(
+fn allocate_instances(memory_allocator: &Arc<GenericMemoryAllocator<FreeListAllocator>>, capacity: u64) -> Subbuffer<[Square]> {
+    Buffer::new_slice(
         memory_allocator.clone(),
         BufferCreateInfo {
-            usage: BufferUsage::INDEX_BUFFER,
+            usage: BufferUsage::VERTEX_BUFFER,
             ..Default::default()
-        }, 
+        },
         AllocationCreateInfo {
             memory_type_filter: MemoryTypeFilter::PREFER_DEVICE | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
             ..Default::default()
-        }, 
-        indices
+        },
+        capacity
     )
-    .unwrap();
+    .expect("Failed to allocate instance buffer")
+}
+
+// The rectangle of one instance of the QuadMesh, in render units
+#[derive(BufferContents, Vertex, Debug, Clone, Copy)]
+#[repr(C)]
+pub(super) struct Square {
+    #[format(R32G32_SFLOAT)]
+    offset: [f32; 2],
+    #[format(R32G32_SFLOAT)]
+    size: [f32; 2],
+    #[format(R32G32B32_SFLOAT)]
+    color: [f32; 3]
+}
 
-    (vertex_buffer, index_buffer)
+impl Square {
+    fn from_game_units(arena: &Arena, x: f32, y: f32, width: f32, height: f32, color: [f32; 3]) -> Self {
+        Square {
+            // Render units grow downwards, so the top edge is the offset
+            offset: [
+                game_unit_to_render_unit(x, arena.width) - 1.0,
+                1.0 - game_unit_to_render_unit(y + height, arena.height)
+            ],
+            size: [game_unit_to_render_unit(width, arena.width), game_unit_to_render_unit(height, arena.height)],
+            color
+        }
+    }
 }
 
-#[derive(Debug)]
-struct Square {
-    x: f32,
-    y: f32,
-    width: f32,
-    height: f32
+pub(super) fn letterbox_viewport(window_size: PhysicalSize<u32>, arena: &Arena) -> Viewport {
+    let area = ArenaViewport::letterbox(window_size, arena);
+    Viewport {
+        offset: area.offset,
+        extent: area.extent,
+        depth_range: 0.0..=1.0
+    }
 }
 
-fn game_unit_to_render_unit(game_unit: f32) -> f32 {
-    game_unit / 50.0 // TODO: generic scale and offset
+// Render units span 2 across the arena in each direction
+fn game_unit_to_render_unit(game_unit: f32, arena_size: f32) -> f32 {
+    game_unit * 2.0 / arena_size
 }
 
@@ -0,0 +1,182 @@
+use std::{cell::RefCell, rc::Rc, str::FromStr};
+
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+#[cfg(feature = "window")]
+use winit::{event_loop::EventLoop, window::{Fullscreen, Window, WindowBuilder}};
+
+use crate::{Arena, GameObject};
+
+use super::{Label, Theme};
+
+pub trait Renderer {
+    fn draw(&mut self, game_objects: &[Rc<RefCell<dyn GameObject>>], labels: &[Label]);
+
+    fn on_window_resized(&mut self) {}
+
+    // Converts a position in window pixels, e.g. the mouse cursor's, to game units
+    fn window_to_game_units(&self, position: PhysicalPosition<f64>) -> (f32, f32);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RendererKind {
+    VULKAN,
+    SOFTWARE,
+    // ANSI escapes in the terminal the game was started from
+    TERMINAL
+}
+
+impl FromStr for RendererKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "VULKAN" => Ok(RendererKind::VULKAN),
+            "SOFTWARE" => Ok(RendererKind::SOFTWARE),
+            "TERMINAL" => Ok(RendererKind::TERMINAL),
+            _ => Err(format!("Unknown renderer {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WindowSettings {
+    // In pixels, the platform's default size when None
+    pub size: Option<(u32, u32)>,
+    pub fullscreen: bool,
+    pub theme: Theme,
+}
+
+#[cfg(feature = "window")]
+pub(super) fn build_window(event_loop: &EventLoop<()>, window_settings: WindowSettings) -> Window {
+    let mut window_builder = WindowBuilder::new();
+    if let Some((width, height)) = window_settings.size {
+        window_builder = window_builder.with_inner_size(PhysicalSize::new(width, height));
+    }
+    if window_settings.fullscreen {
+        window_builder = window_builder.with_fullscreen(Some(Fullscreen::Borderless(None)));
+    }
+    window_builder.build(event_loop).expect("Failed to create window")
+}
+
+// A rectangle to draw in game units, with (x, y) being the bottom left corner
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct ColoredRect {
+    pub(super) x: f32,
+    pub(super) y: f32,
+    pub(super) width: f32,
+    pub(super) height: f32,
+    pub(super) color: [f32; 3],
+}
+
+// Every rectangle to draw, in each game object's own color and with labels in the text color
+pub(super) fn game_rects(game_objects: &[Rc<RefCell<dyn GameObject>>], labels: &[Label], text_color: [f32; 3]) -> Vec<ColoredRect> {
+    let mut rects: Vec<ColoredRect> = game_objects
+        .iter()
+        .map(|game_object| {
+            let game_object = game_object.borrow();
+            let state = game_object.get_state();
+            ColoredRect { x: state.x, y: state.y, width: state.width, height: state.height, color: state.color }
+        })
+        .collect();
+
+    for label in labels {
+        rects.extend(
+            label
+                .cells()
+                .into_iter()
+                .map(|(x, y, width, height)| ColoredRect { x, y, width, height, color: text_color })
+        );
+    }
+
+    rects
+}
+
+// The area of a render target, in pixels, that the arena is drawn into
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct ArenaViewport {
+    pub(super) offset: [f32; 2],
+    pub(super) extent: [f32; 2],
+}
+
+impl ArenaViewport {
+    // The largest area with the arena's aspect ratio, centered in the target
+    pub(super) fn letterbox(target_size: PhysicalSize<u32>, arena: &Arena) -> Self {
+        let target_width = target_size.width.max(1) as f32;
+        let target_height = target_size.height.max(1) as f32;
+        let scale = (target_width / arena.width).min(target_height / arena.height);
+        let extent = [(arena.width * scale).max(1.0), (arena.height * scale).max(1.0)];
+
+        Self {
+            offset: [((target_width - extent[0]) / 2.0).floor(), ((target_height - extent[1]) / 2.0).floor()],
+            extent
+        }
+    }
+
+    // Pixel rows grow downwards while game units grow upwards
+    pub(super) fn game_units_at(&self, arena: &Arena, position: PhysicalPosition<f64>) -> (f32, f32) {
+        let x = (position.x as f32 - self.offset[0]) / self.extent[0] * arena.width;
+        let y = (1.0 - (position.y as f32 - self.offset[1]) / self.extent[1]) * arena.height;
+        (x, y)
+    }
+
+    // The left, top, right and bottom pixel edges of a rectangle in game units
+    pub(super) fn pixel_rect(&self, arena: &Arena, x: f32, y: f32, width: f32, height: f32) -> [f32; 4] {
+        let scale_x = self.extent[0] / arena.width;
+        let scale_y = self.extent[1] / arena.height;
+        [
+            self.offset[0] + x * scale_x,
+            self.offset[1] + (arena.height - y - height) * scale_y,
+            self.offset[0] + (x + width) * scale_x,
+            self.offset[1] + (arena.height - y) * scale_y,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARENA: Arena = Arena { width: 100.0, height: 50.0 };
+
+    fn assert_near((x, y): (f32, f32), (expected_x, expected_y): (f32, f32)) {
+        assert!((x - expected_x).abs() < 1e-4 && (y - expected_y).abs() < 1e-4, "({}, {}) != ({}, {})", x, y, expected_x, expected_y);
+    }
+
+    #[test]
+    fn letterbox_wide_target() {
+        // Bars on the left and right
+        let viewport = ArenaViewport::letterbox(PhysicalSize::new(400, 100), &ARENA);
+        assert_eq!(viewport, ArenaViewport { offset: [100.0, 0.0], extent: [200.0, 100.0] });
+    }
+
+    #[test]
+    fn letterbox_tall_target() {
+        // Bars on the top and bottom
+        let viewport = ArenaViewport::letterbox(PhysicalSize::new(200, 301), &ARENA);
+        assert_eq!(viewport, ArenaViewport { offset: [0.0, 100.0], extent: [200.0, 100.0] });
+    }
+
+    #[test]
+    fn letterbox_exact_fit() {
+        let viewport = ArenaViewport::letterbox(PhysicalSize::new(300, 150), &ARENA);
+        assert_eq!(viewport, ArenaViewport { offset: [0.0, 0.0], extent: [300.0, 150.0] });
+        assert_eq!(viewport.pixel_rect(&ARENA, 0.0, 0.0, ARENA.width, ARENA.height), [0.0, 0.0, 300.0, 150.0]);
+    }
+
+    #[test]
+    fn pixel_rect_and_game_units_at_invert_each_other() {
+        for viewport in [
+            ArenaViewport::letterbox(PhysicalSize::new(400, 100), &ARENA),
+            ArenaViewport::letterbox(PhysicalSize::new(200, 301), &ARENA),
+        ] {
+            // The bottom left and top right corners of a rectangle, which pixel rows flip
+            let [left, top, right, bottom] = viewport.pixel_rect(&ARENA, 20.0, 10.0, 30.0, 15.0);
+            assert_near(viewport.game_units_at(&ARENA, PhysicalPosition::new(left as f64, bottom as f64)), (20.0, 10.0));
+            assert_near(viewport.game_units_at(&ARENA, PhysicalPosition::new(right as f64, top as f64)), (50.0, 25.0));
+        }
+
+        // Outside the arena, e.g. the cursor over a bar
+        let viewport = ArenaViewport::letterbox(PhysicalSize::new(400, 100), &ARENA);
+        assert_near(viewport.game_units_at(&ARENA, PhysicalPosition::new(50.0, 0.0)), (-25.0, 50.0));
+    }
+}
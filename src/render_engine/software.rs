@@ -0,0 +1,70 @@
+use std::{cell::RefCell, rc::Rc};
+
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+
+use crate::{Arena, GameObject};
+
+use super::{renderer::{game_rects, ArenaViewport}, Frame, Label, Renderer, Theme};
+
+// Rasterizes into a Frame in memory, for machines without a Vulkan driver
+pub struct SoftwareRenderer {
+    frame: Frame,
+    arena: Arena,
+    theme: Theme,
+}
+
+impl SoftwareRenderer {
+    pub fn new(width: u32, height: u32, arena: Arena, theme: Theme) -> Self {
+        Self { frame: Frame::new(width, height), arena, theme }
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.frame = Frame::new(width, height);
+    }
+
+    pub fn frame(&self) -> &Frame {
+        &self.frame
+    }
+
+    fn viewport(&self) -> ArenaViewport {
+        ArenaViewport::letterbox(PhysicalSize::new(self.frame.width, self.frame.height), &self.arena)
+    }
+
+    // Fills the pixels whose centers are inside the left, top, right and bottom edges
+    fn fill(&mut self, [left, top, right, bottom]: [f32; 4], color: [f32; 3]) {
+        let rgba = [
+            (color[0].clamp(0.0, 1.0) * 255.0).round() as u8,
+            (color[1].clamp(0.0, 1.0) * 255.0).round() as u8,
+            (color[2].clamp(0.0, 1.0) * 255.0).round() as u8,
+            255,
+        ];
+        let x_range = left.round().max(0.0) as u32..right.round().clamp(0.0, self.frame.width as f32) as u32;
+        let y_range = top.round().max(0.0) as u32..bottom.round().clamp(0.0, self.frame.height as f32) as u32;
+
+        for y in y_range {
+            for x in x_range.clone() {
+                let i = ((y * self.frame.width + x) * 4) as usize;
+                self.frame.pixels[i..i + 4].copy_from_slice(&rgba);
+            }
+        }
+    }
+}
+
+impl Renderer for SoftwareRenderer {
+    fn draw(&mut self, game_objects: &[Rc<RefCell<dyn GameObject>>], labels: &[Label]) {
+        let viewport = self.viewport();
+        let arena = self.arena;
+
+        let theme = self.theme;
+
+        self.fill([0.0, 0.0, self.frame.width as f32, self.frame.height as f32], theme.border);
+        self.fill(viewport.pixel_rect(&arena, 0.0, 0.0, arena.width, arena.height), theme.arena);
+        for rect in game_rects(game_objects, labels, theme.text) {
+            self.fill(viewport.pixel_rect(&arena, rect.x, rect.y, rect.width, rect.height), rect.color);
+        }
+    }
+
+    fn window_to_game_units(&self, position: PhysicalPosition<f64>) -> (f32, f32) {
+        self.viewport().game_units_at(&self.arena, position)
+    }
+}
@@ -0,0 +1,58 @@
+use std::{cell::RefCell, num::NonZeroU32, rc::Rc};
+
+use softbuffer::{Context, Surface};
+use winit::{dpi::PhysicalPosition, event_loop::EventLoop, window::Window};
+
+use crate::{Arena, GameObject};
+
+use super::{renderer::build_window, Label, Renderer, SoftwareRenderer, WindowSettings};
+
+// Shows the SoftwareRenderer's frames in a window
+pub struct SoftwareWindowRenderer {
+    // Declared before the window so that they are dropped first
+    surface: Surface,
+    _context: Context,
+    window: Window,
+    renderer: SoftwareRenderer,
+}
+
+impl SoftwareWindowRenderer {
+    pub fn new(event_loop: &EventLoop<()>, window_settings: WindowSettings, arena: Arena) -> Self {
+        let window = build_window(event_loop, window_settings);
+        // SAFETY: the window is owned by this renderer and outlives the context and surface
+        let context = unsafe { Context::new(&window) }.expect("Failed to create software context");
+        let surface = unsafe { Surface::new(&context, &window) }.expect("Failed to create software surface");
+
+        let size = window.inner_size();
+        let renderer = SoftwareRenderer::new(size.width, size.height, arena, window_settings.theme);
+
+        Self { surface, _context: context, window, renderer }
+    }
+}
+
+impl Renderer for SoftwareWindowRenderer {
+    fn draw(&mut self, game_objects: &[Rc<RefCell<dyn GameObject>>], labels: &[Label]) {
+        self.renderer.draw(game_objects, labels);
+
+        let frame = self.renderer.frame();
+        let (Some(width), Some(height)) = (NonZeroU32::new(frame.width), NonZeroU32::new(frame.height)) else {
+            return; // Minimized
+        };
+        self.surface.resize(width, height).expect("Failed to resize software surface");
+
+        let mut buffer = self.surface.buffer_mut().expect("Failed to get software surface buffer");
+        for (pixel, rgba) in buffer.iter_mut().zip(frame.pixels.chunks_exact(4)) {
+            *pixel = (rgba[0] as u32) << 16 | (rgba[1] as u32) << 8 | rgba[2] as u32;
+        }
+        buffer.present().expect("Failed to present software frame");
+    }
+
+    fn on_window_resized(&mut self) {
+        let size = self.window.inner_size();
+        self.renderer.resize(size.width, size.height);
+    }
+
+    fn window_to_game_units(&self, position: PhysicalPosition<f64>) -> (f32, f32) {
+        self.renderer.window_to_game_units(position)
+    }
+}
@@ -0,0 +1,116 @@
+use std::{cell::RefCell, io::{self, Stdout, Write}, rc::Rc};
+
+use crossterm::{cursor::{Hide, MoveTo, Show}, execute, queue, style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor}, terminal::{self, EnterAlternateScreen, LeaveAlternateScreen}};
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+
+use crate::{Arena, GameObject};
+
+use super::{renderer::ArenaViewport, Label, Renderer, SoftwareRenderer, Theme};
+
+// Used when the terminal's size can't be queried
+const DEFAULT_TERMINAL_SIZE: (u16, u16) = (80, 24);
+
+// Draws into the terminal with ANSI escapes. Every character cell shows two pixels, the top one
+// as the foreground of an upper half block and the bottom one as the background, so pixels are
+// roughly square in most fonts. Labels are written as text since their glyphs would be too
+// small to read at that resolution
+pub struct TerminalRenderer {
+    stdout: Stdout,
+    buffer: Vec<u8>,
+    renderer: SoftwareRenderer,
+    arena: Arena,
+    theme: Theme,
+}
+
+impl TerminalRenderer {
+    // Switches to the alternate screen and to raw mode, which also lets a TerminalBackend read
+    // single key presses. Both are undone when the renderer is dropped
+    pub fn new(arena: Arena, theme: Theme) -> io::Result<Self> {
+        let mut stdout = io::stdout();
+        terminal::enable_raw_mode()?;
+        execute!(stdout, EnterAlternateScreen, Hide)?;
+
+        let (columns, rows) = terminal::size().unwrap_or(DEFAULT_TERMINAL_SIZE);
+        let renderer = SoftwareRenderer::new(columns as u32, rows as u32 * 2, arena, theme);
+
+        Ok(Self { stdout, buffer: Vec::new(), renderer, arena, theme })
+    }
+
+    fn present(&mut self, labels: &[Label]) -> io::Result<()> {
+        let frame = self.renderer.frame();
+        let pixel = |x: u32, y: u32| {
+            let i = ((y * frame.width + x) * 4) as usize;
+            Color::Rgb { r: frame.pixels[i], g: frame.pixels[i + 1], b: frame.pixels[i + 2] }
+        };
+
+        // Colors are only sent when they change, which keeps most frames to a few kilobytes
+        self.buffer.clear();
+        let (mut foreground, mut background) = (None, None);
+        for row in 0..frame.height / 2 {
+            queue!(self.buffer, MoveTo(0, row as u16))?;
+            for column in 0..frame.width {
+                let (top, bottom) = (pixel(column, row * 2), pixel(column, row * 2 + 1));
+                if background != Some(bottom) {
+                    queue!(self.buffer, SetBackgroundColor(bottom))?;
+                    background = Some(bottom);
+                }
+                if top == bottom {
+                    queue!(self.buffer, Print(' '))?;
+                    continue;
+                }
+                if foreground != Some(top) {
+                    queue!(self.buffer, SetForegroundColor(top))?;
+                    foreground = Some(top);
+                }
+                queue!(self.buffer, Print('▀'))?;
+            }
+        }
+
+        // Each label is one line of text, centered where its glyphs would have been drawn
+        let viewport = ArenaViewport::letterbox(PhysicalSize::new(frame.width, frame.height), &self.arena);
+        let color = |[r, g, b]: [f32; 3]| Color::Rgb {
+            r: (r.clamp(0.0, 1.0) * 255.0).round() as u8,
+            g: (g.clamp(0.0, 1.0) * 255.0).round() as u8,
+            b: (b.clamp(0.0, 1.0) * 255.0).round() as u8,
+        };
+        queue!(self.buffer, SetForegroundColor(color(self.theme.text)), SetBackgroundColor(color(self.theme.arena)))?;
+        for label in labels {
+            let [left, top, right, bottom] = viewport.pixel_rect(&self.arena, label.x, label.y, label.width(), label.height());
+            let characters = label.text.chars().count() as f32;
+            let column = ((left + right - characters) / 2.0).round().clamp(0.0, (frame.width as f32 - characters).max(0.0));
+            let row = ((top + bottom) / 4.0).floor().clamp(0.0, (frame.height / 2).saturating_sub(1) as f32);
+            queue!(self.buffer, MoveTo(column as u16, row as u16), Print(&label.text))?;
+        }
+        queue!(self.buffer, ResetColor)?;
+
+        self.stdout.write_all(&self.buffer)?;
+        self.stdout.flush()
+    }
+}
+
+impl Renderer for TerminalRenderer {
+    fn draw(&mut self, game_objects: &[Rc<RefCell<dyn GameObject>>], labels: &[Label]) {
+        let (columns, rows) = terminal::size().unwrap_or(DEFAULT_TERMINAL_SIZE);
+        let frame = self.renderer.frame();
+        if (frame.width, frame.height) != (columns as u32, rows as u32 * 2) {
+            self.renderer.resize(columns as u32, rows as u32 * 2);
+            // Clears what the old frame left outside the new one
+            let _ = queue!(self.stdout, ResetColor, terminal::Clear(terminal::ClearType::All));
+        }
+
+        self.renderer.draw(game_objects, &[]);
+        self.present(labels).expect("Failed to write to the terminal");
+    }
+
+    // Positions are in character cells, each of which covers two pixel rows
+    fn window_to_game_units(&self, position: PhysicalPosition<f64>) -> (f32, f32) {
+        self.renderer.window_to_game_units(PhysicalPosition::new(position.x, position.y * 2.0 + 1.0))
+    }
+}
+
+impl Drop for TerminalRenderer {
+    fn drop(&mut self) {
+        let _ = execute!(self.stdout, ResetColor, Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
@@ -0,0 +1,90 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+// RGB colors from 0.0 to 1.0 for everything that gets drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    // Fills the letterbox or pillarbox bars around the arena
+    pub border: [f32; 3],
+    pub arena: [f32; 3],
+    pub left_paddle: [f32; 3],
+    pub right_paddle: [f32; 3],
+    pub ball: [f32; 3],
+    // The score and other HUD labels
+    pub text: [f32; 3],
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        ThemeName::CLASSIC.theme()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+pub enum ThemeName {
+    #[default]
+    #[serde(rename = "classic")]
+    CLASSIC,
+    #[serde(rename = "neon")]
+    NEON,
+    #[serde(rename = "high-contrast")]
+    CONTRAST,
+    // Tells the paddles apart by blue and orange, which stay distinct with the common forms of
+    // color blindness
+    #[serde(rename = "colorblind-safe")]
+    COLORBLIND
+}
+
+impl ThemeName {
+    pub fn theme(&self) -> Theme {
+        match self {
+            ThemeName::CLASSIC => Theme {
+                border: [0.15, 0.15, 0.15],
+                arena: [0.0, 0.0, 0.0],
+                left_paddle: [1.0, 1.0, 1.0],
+                right_paddle: [1.0, 1.0, 1.0],
+                ball: [1.0, 1.0, 1.0],
+                text: [1.0, 1.0, 1.0],
+            },
+            ThemeName::NEON => Theme {
+                border: [0.08, 0.0, 0.12],
+                arena: [0.02, 0.0, 0.05],
+                left_paddle: [0.0, 1.0, 0.9],
+                right_paddle: [1.0, 0.1, 0.8],
+                ball: [1.0, 1.0, 0.3],
+                text: [0.5, 1.0, 0.3],
+            },
+            ThemeName::CONTRAST => Theme {
+                border: [0.5, 0.5, 0.5],
+                arena: [0.0, 0.0, 0.0],
+                left_paddle: [1.0, 1.0, 1.0],
+                right_paddle: [1.0, 1.0, 1.0],
+                ball: [1.0, 1.0, 0.0],
+                text: [1.0, 1.0, 1.0],
+            },
+            ThemeName::COLORBLIND => Theme {
+                border: [0.2, 0.2, 0.2],
+                arena: [0.0, 0.0, 0.0],
+                left_paddle: [0.34, 0.71, 0.91],
+                right_paddle: [0.9, 0.62, 0.0],
+                ball: [1.0, 1.0, 1.0],
+                text: [1.0, 1.0, 1.0],
+            },
+        }
+    }
+}
+
+impl FromStr for ThemeName {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().replace('-', "_").as_str() {
+            "CLASSIC" => Ok(ThemeName::CLASSIC),
+            "NEON" => Ok(ThemeName::NEON),
+            "HIGH_CONTRAST" => Ok(ThemeName::CONTRAST),
+            "COLORBLIND_SAFE" => Ok(ThemeName::COLORBLIND),
+            _ => Err(format!("Unknown theme {}", s)),
+        }
+    }
+}
@@ -0,0 +1,3 @@
+mod replay;
+
+pub use replay::*;
\ No newline at end of file
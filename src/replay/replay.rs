@@ -0,0 +1,388 @@
+use std::{error::Error, fmt, fs::File, io::{self, BufRead, BufReader, BufWriter, Write}, path::Path};
+
+use crate::{Arena, BallConfig, ControllerKind, GameConfig, MatchRules, MatchSetup, MoveCommand, PaddleConfig, Side, TickInput};
+
+const REPLAY_HEADER: &str = "PONG REPLAY";
+const REPLAY_VERSION: u32 = 8;
+// Over four hours at 17 ms per tick. Replays are shared in bug reports, so a run length
+// must not be able to make reading one allocate without bound
+const MAX_REPLAY_TICKS: usize = 1_000_000;
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    UnsupportedVersion(u32),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(error) => write!(f, "Failed to access replay file: {}", error),
+            ReplayError::UnsupportedVersion(version) => write!(f, "Unsupported replay version {}", version),
+            ReplayError::Parse { line, message } => write!(f, "Invalid replay at line {}: {}", line, message),
+        }
+    }
+}
+
+impl Error for ReplayError {}
+
+impl From<io::Error> for ReplayError {
+    fn from(error: io::Error) -> Self {
+        ReplayError::Io(error)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Replay {
+    pub setup: MatchSetup,
+    pub inputs: Vec<TickInput>,
+}
+
+impl Replay {
+    pub fn new(setup: MatchSetup) -> Self {
+        Self { setup, inputs: Vec::new() }
+    }
+
+    pub fn record(&mut self, input: &TickInput) {
+        self.inputs.push(input.clone());
+    }
+
+    // The command held on one paddle on every tick, e.g. for a ReplayController
+    pub fn paddle_commands(&self, side: Side) -> Vec<Option<MoveCommand>> {
+        self.inputs
+            .iter()
+            .map(|input| match side {
+                Side::LEFT => input.left_paddle,
+                Side::RIGHT => input.right_paddle,
+            })
+            .collect()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ReplayError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    // Inputs are run-length encoded, one line per run: "<ticks> [L=<command>] [R=<command>] [S] [P] [X]"
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "{} {}", REPLAY_HEADER, REPLAY_VERSION)?;
+        writeln!(writer, "seed {}", self.setup.seed)?;
+        let config = &self.setup.config;
+        writeln!(writer, "arena_width {}", config.arena.width)?;
+        writeln!(writer, "arena_height {}", config.arena.height)?;
+        writeln!(writer, "paddle_width {}", config.paddle.width)?;
+        writeln!(writer, "paddle_height {}", config.paddle.height)?;
+        writeln!(writer, "paddle_max_speed {}", config.paddle.max_speed)?;
+        writeln!(writer, "paddle_acceleration {}", config.paddle.acceleration)?;
+        writeln!(writer, "paddle_deceleration {}", config.paddle.deceleration)?;
+        writeln!(writer, "ball_size {}", config.ball.size)?;
+        writeln!(writer, "ball_speed {}", config.ball.base_speed)?;
+        writeln!(writer, "ball_acceleration {}", config.ball.acceleration)?;
+        writeln!(writer, "ball_max_speed {}", config.ball.max_speed)?;
+        writeln!(writer, "points_to_win {}", config.rules.points_to_win)?;
+        writeln!(writer, "win_by {}", config.rules.win_by)?;
+        writeln!(writer, "left {}", controller_kind_name(self.setup.left_controller))?;
+        writeln!(writer, "right {}", controller_kind_name(self.setup.right_controller))?;
+        writeln!(writer, "inputs")?;
+
+        let mut i = 0;
+        while i < self.inputs.len() {
+            let input = &self.inputs[i];
+            let mut run = 1;
+            while i + run < self.inputs.len() && self.inputs[i + run] == *input {
+                run += 1;
+            }
+
+            write!(writer, "{}", run)?;
+            if let Some(command) = input.left_paddle {
+                write!(writer, " L={}", encode_command(command))?;
+            }
+            if let Some(command) = input.right_paddle {
+                write!(writer, " R={}", encode_command(command))?;
+            }
+            if input.serve {
+                write!(writer, " S")?;
+            }
+            if input.toggle_pause {
+                write!(writer, " P")?;
+            }
+            if input.restart {
+                write!(writer, " X")?;
+            }
+            writeln!(writer)?;
+
+            i += run;
+        }
+
+        Ok(())
+    }
+
+    pub fn read<R: BufRead>(reader: R) -> Result<Self, ReplayError> {
+        let mut lines = reader.lines().enumerate().map(|(i, line)| (i + 1, line));
+
+        let (line_number, header) = next_line(&mut lines)?;
+        let version = header
+            .strip_prefix(REPLAY_HEADER)
+            .and_then(|version| version.trim().parse::<u32>().ok())
+            .ok_or_else(|| parse_error(line_number, "missing replay header"))?;
+        if version != REPLAY_VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+
+        let seed = parse_number(next_field(&mut lines, "seed")?)?;
+        let config_end;
+        let config = GameConfig {
+            arena: Arena {
+                width: parse_number(next_field(&mut lines, "arena_width")?)?,
+                height: parse_number(next_field(&mut lines, "arena_height")?)?
+            },
+            paddle: PaddleConfig {
+                width: parse_number(next_field(&mut lines, "paddle_width")?)?,
+                height: parse_number(next_field(&mut lines, "paddle_height")?)?,
+                max_speed: parse_number(next_field(&mut lines, "paddle_max_speed")?)?,
+                acceleration: parse_number(next_field(&mut lines, "paddle_acceleration")?)?,
+                deceleration: parse_number(next_field(&mut lines, "paddle_deceleration")?)?
+            },
+            ball: BallConfig {
+                size: parse_number(next_field(&mut lines, "ball_size")?)?,
+                base_speed: parse_number(next_field(&mut lines, "ball_speed")?)?,
+                acceleration: parse_number(next_field(&mut lines, "ball_acceleration")?)?,
+                max_speed: parse_number(next_field(&mut lines, "ball_max_speed")?)?
+            },
+            rules: MatchRules {
+                points_to_win: parse_number(next_field(&mut lines, "points_to_win")?)?,
+                win_by: {
+                    let win_by = next_field(&mut lines, "win_by")?;
+                    config_end = win_by.0;
+                    parse_number(win_by)?
+                }
+            }
+        };
+        // Replays are read without going through GameConfig::load
+        config.validate().map_err(|error| parse_error(config_end, &error.to_string()))?;
+        let left_controller = parse_controller_kind(next_field(&mut lines, "left")?)?;
+        let right_controller = parse_controller_kind(next_field(&mut lines, "right")?)?;
+
+        let (line_number, marker) = next_line(&mut lines)?;
+        if marker.trim() != "inputs" {
+            return Err(parse_error(line_number, "expected inputs"));
+        }
+
+        let mut replay = Replay::new(MatchSetup {
+            seed,
+            config,
+            left_controller,
+            right_controller
+        });
+
+        for (line_number, line) in lines {
+            let line = line?;
+            let mut tokens = line.split_whitespace();
+            let run: usize = match tokens.next() {
+                Some(run) => run.parse().map_err(|_| parse_error(line_number, "invalid tick count"))?,
+                None => continue,
+            };
+
+            let mut input = TickInput::default();
+            for token in tokens {
+                match token {
+                    "S" => input.serve = true,
+                    "P" => input.toggle_pause = true,
+                    "X" => input.restart = true,
+                    _ => {
+                        if let Some(command) = token.strip_prefix("L=") {
+                            input.left_paddle = Some(decode_command(command, line_number)?);
+                        } else if let Some(command) = token.strip_prefix("R=") {
+                            input.right_paddle = Some(decode_command(command, line_number)?);
+                        } else {
+                            return Err(parse_error(line_number, &format!("unknown token {}", token)));
+                        }
+                    }
+                }
+            }
+
+            if run > MAX_REPLAY_TICKS - replay.inputs.len() {
+                return Err(parse_error(line_number, &format!("replay is longer than {} ticks", MAX_REPLAY_TICKS)));
+            }
+            for _ in 0..run {
+                replay.inputs.push(input.clone());
+            }
+        }
+
+        Ok(replay)
+    }
+}
+
+type NumberedLines<'a> = dyn Iterator<Item = (usize, io::Result<String>)> + 'a;
+
+fn next_line(lines: &mut NumberedLines) -> Result<(usize, String), ReplayError> {
+    match lines.next() {
+        Some((line_number, line)) => Ok((line_number, line?)),
+        None => Err(ReplayError::Io(io::Error::new(io::ErrorKind::UnexpectedEof, "replay file ended early"))),
+    }
+}
+
+fn next_field(lines: &mut NumberedLines, name: &str) -> Result<(usize, String), ReplayError> {
+    let (line_number, line) = next_line(lines)?;
+    match line.split_once(' ') {
+        Some((field, value)) if field == name => Ok((line_number, value.trim().to_string())),
+        _ => Err(parse_error(line_number, &format!("expected {}", name))),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>((line_number, value): (usize, String)) -> Result<T, ReplayError> {
+    value.parse().map_err(|_| parse_error(line_number, &format!("invalid number {}", value)))
+}
+
+fn parse_controller_kind((line_number, value): (usize, String)) -> Result<ControllerKind, ReplayError> {
+    if value == "HUMAN" {
+        return Ok(ControllerKind::HUMAN);
+    }
+
+    match value.strip_prefix("AI ").map(str::parse) {
+        Some(Ok(difficulty)) => Ok(ControllerKind::AI(difficulty)),
+        _ => Err(parse_error(line_number, &format!("unknown controller {}", value))),
+    }
+}
+
+fn controller_kind_name(controller_kind: ControllerKind) -> String {
+    match controller_kind {
+        ControllerKind::HUMAN => "HUMAN".to_string(),
+        ControllerKind::AI(difficulty) => format!("AI {}", difficulty.name()),
+    }
+}
+
+fn encode_command(command: MoveCommand) -> String {
+    match command {
+        MoveCommand::UP => "U".to_string(),
+        MoveCommand::DOWN => "D".to_string(),
+        MoveCommand::TARGET(y) => format!("T{}", y),
+        MoveCommand::AXIS(axis) => format!("A{}", axis),
+    }
+}
+
+fn decode_command(command: &str, line_number: usize) -> Result<MoveCommand, ReplayError> {
+    match command {
+        "U" => Ok(MoveCommand::UP),
+        "D" => Ok(MoveCommand::DOWN),
+        _ => command
+            .strip_prefix('T')
+            .and_then(|y| y.parse().ok())
+            .map(MoveCommand::TARGET)
+            .or_else(|| command.strip_prefix('A').and_then(|axis| axis.parse().ok()).map(MoveCommand::AXIS))
+            .ok_or_else(|| parse_error(line_number, &format!("unknown move command {}", command))),
+    }
+}
+
+fn parse_error(line: usize, message: &str) -> ReplayError {
+    ReplayError::Parse { line, message: message.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Difficulty;
+
+    fn replay() -> Replay {
+        let mut config = GameConfig::default();
+        config.arena.width = 24.5;
+        config.rules.points_to_win = 5;
+        let mut replay = Replay::new(MatchSetup {
+            seed: 1234,
+            config,
+            left_controller: ControllerKind::HUMAN,
+            right_controller: ControllerKind::AI(Difficulty::HARD)
+        });
+
+        let inputs = [
+            (3, TickInput { serve: true, ..TickInput::default() }),
+            (1, TickInput { left_paddle: Some(MoveCommand::UP), toggle_pause: true, ..TickInput::default() }),
+            (4, TickInput { left_paddle: Some(MoveCommand::DOWN), right_paddle: Some(MoveCommand::TARGET(3.25)), ..TickInput::default() }),
+            (2, TickInput { right_paddle: Some(MoveCommand::AXIS(-0.125)), restart: true, ..TickInput::default() }),
+            (1, TickInput::default()),
+        ];
+        for (run, input) in inputs {
+            for _ in 0..run {
+                replay.record(&input);
+            }
+        }
+        replay
+    }
+
+    fn write_string(replay: &Replay) -> String {
+        let mut buffer = Vec::new();
+        replay.write(&mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let replay = replay();
+        let text = write_string(&replay);
+
+        // Equal ticks in a row are stored as one run
+        let runs: Vec<&str> = text.lines().skip_while(|line| *line != "inputs").skip(1).collect();
+        assert_eq!(runs, ["3 S", "1 L=U P", "4 L=D R=T3.25", "2 R=A-0.125 X", "1"]);
+
+        assert_eq!(Replay::read(text.as_bytes()).unwrap(), replay);
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let text = write_string(&replay()).replacen(&REPLAY_VERSION.to_string(), "1", 1);
+
+        match Replay::read(text.as_bytes()) {
+            Err(ReplayError::UnsupportedVersion(1)) => (),
+            result => panic!("Expected an unsupported version, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_configs() {
+        let text = write_string(&replay()).replace("win_by 2", "win_by 0");
+        let win_by_line = text.lines().position(|line| line.starts_with("win_by")).unwrap() + 1;
+
+        match Replay::read(text.as_bytes()) {
+            Err(ReplayError::Parse { line, message }) => {
+                assert_eq!(line, win_by_line);
+                assert_eq!(message, "Invalid game config: rules.win_by must be at least 1");
+            },
+            result => panic!("Expected a parse error, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn rejects_overly_long_replays() {
+        let text = write_string(&replay()) + "99999999999 S\n";
+
+        match Replay::read(text.as_bytes()) {
+            Err(ReplayError::Parse { message, .. }) => assert_eq!(message, "replay is longer than 1000000 ticks"),
+            result => panic!("Expected a parse error, got {:?}", result),
+        }
+
+        // Runs that are fine on their own add up
+        let text = write_string(&replay()) + "600000\n600000\n";
+        assert!(Replay::read(text.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_tokens() {
+        let text = write_string(&replay()) + "2 L=U Q\n";
+        let line_count = text.lines().count();
+
+        match Replay::read(text.as_bytes()) {
+            Err(ReplayError::Parse { line, message }) => {
+                assert_eq!(line, line_count);
+                assert_eq!(message, "unknown token Q");
+            },
+            result => panic!("Expected a parse error, got {:?}", result),
+        }
+    }
+}
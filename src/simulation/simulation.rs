@@ -0,0 +1,277 @@
+use std::{cell::RefCell, rc::Rc};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{Ball, ControllerKind, GameConfig, GameObject, GameObjectState, GameState, MoveCommand, Paddle, PaddleController, Side, Theme};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MatchSetup {
+    pub seed: u64,
+    pub config: GameConfig,
+    pub left_controller: ControllerKind,
+    pub right_controller: ControllerKind,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TickInput {
+    pub left_paddle: Option<MoveCommand>,
+    pub right_paddle: Option<MoveCommand>,
+    pub serve: bool,
+    pub toggle_pause: bool,
+    pub restart: bool,
+}
+
+pub struct Simulation {
+    game_objects: Vec<Rc<RefCell<dyn GameObject>>>,
+    left_paddle: Rc<RefCell<Paddle>>,
+    right_paddle: Rc<RefCell<Paddle>>,
+    ball: Rc<RefCell<Ball>>,
+    game_state: GameState,
+    setup: MatchSetup,
+    rng: StdRng,
+    ticks: u64,
+}
+
+impl Simulation {
+    pub fn new(setup: MatchSetup) -> Self {
+        Self::with_theme(setup, &Theme::default())
+    }
+
+    // Colors the paddles and the ball with `theme`, which only matters for drawing
+    pub fn with_theme(setup: MatchSetup, theme: &Theme) -> Self {
+        let (rng, left_seed, right_seed) = seeded_rng(setup.seed);
+        let left_controller = setup.left_controller.controller(left_seed);
+        let right_controller = setup.right_controller.controller(right_seed);
+
+        Self::build(setup, theme, left_controller, right_controller, rng)
+    }
+
+    // Uses the given controllers instead of the ones described by `setup`. Controllers that
+    // do not read their input from TickInput will not be captured by replays.
+    pub fn with_controllers(
+        setup: MatchSetup,
+        left_controller: Box<dyn PaddleController>,
+        right_controller: Box<dyn PaddleController>
+    ) -> Self {
+        let (rng, _, _) = seeded_rng(setup.seed);
+        Self::build(setup, &Theme::default(), left_controller, right_controller, rng)
+    }
+
+    fn build(
+        setup: MatchSetup,
+        theme: &Theme,
+        left_controller: Box<dyn PaddleController>,
+        right_controller: Box<dyn PaddleController>,
+        rng: StdRng
+    ) -> Self {
+        let config = setup.config;
+        let left_paddle = Rc::new(RefCell::new(Paddle::new(
+            GameObjectState { 
+                height: config.paddle.height, 
+                width: config.paddle.width,
+                x: 0.0, 
+                y: config.arena.height / 2.0, 
+                color: theme.left_paddle
+            }, 
+            config.paddle.motion(),
+            left_controller
+        )));
+        let ball = Rc::new(RefCell::new(Ball::new(
+            GameObjectState { 
+                height: config.ball.size, 
+                width: config.ball.size,
+                x: config.arena.width / 2.0, 
+                y: config.arena.height / 2.0, 
+                color: theme.ball
+            }, 
+            config.ball.speed()
+        )));
+        let right_paddle = Rc::new(RefCell::new(Paddle::new(
+            GameObjectState { 
+                height: config.paddle.height, 
+                width: config.paddle.width,
+                x: config.arena.width - config.paddle.width, 
+                y: config.arena.height / 2.0, 
+                color: theme.right_paddle
+            },
+            config.paddle.motion(),
+            right_controller
+        )));
+
+        let game_objects: Vec<Rc<RefCell<dyn GameObject>>> = vec![
+            left_paddle.clone(),
+            ball.clone(),
+            right_paddle.clone()
+        ];
+
+        Self {
+            game_objects,
+            left_paddle,
+            right_paddle,
+            ball,
+            game_state: GameState::new(config.rules),
+            setup,
+            rng,
+            ticks: 0
+        }
+    }
+
+    pub fn step(&mut self, input: TickInput) {
+        if input.restart {
+            self.game_state.restart();
+            self.ball.borrow_mut().reset(&self.setup.config.arena);
+        }
+        if input.serve {
+            self.game_state.start();
+            self.game_state.serve();
+        }
+        if input.toggle_pause {
+            self.game_state.toggle_pause();
+        }
+
+        if self.game_state.updates_paddles() {
+            self.left_paddle.borrow_mut().command = input.left_paddle;
+            self.right_paddle.borrow_mut().command = input.right_paddle;
+        }
+
+        self.game_state.tick();
+
+        if self.game_state.take_serve() {
+            let direction = if self.rng.random_bool(0.5) { Side::LEFT } else { Side::RIGHT };
+            self.ball.borrow_mut().serve(direction);
+        }
+
+        for game_object in &self.game_objects {
+            let is_ball = game_object.borrow().as_ball().is_some();
+            if (is_ball && self.game_state.updates_ball()) || (!is_ball && self.game_state.updates_paddles()) {
+                game_object.borrow_mut().update(&self.setup.config.arena, &self.game_objects);
+            }
+        }
+        if !self.game_state.updates_paddles() {
+            self.left_paddle.borrow_mut().skip_update();
+            self.right_paddle.borrow_mut().skip_update();
+        }
+
+        if let Some(side) = self.ball.borrow_mut().take_point() {
+            self.game_state.point_scored(side);
+        }
+
+        self.ticks += 1;
+    }
+
+    pub fn game_objects(&self) -> &Vec<Rc<RefCell<dyn GameObject>>> {
+        &self.game_objects
+    }
+
+    pub fn game_state(&self) -> &GameState {
+        &self.game_state
+    }
+
+    pub fn setup(&self) -> &MatchSetup {
+        &self.setup
+    }
+
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+}
+
+// Returns the match's RNG along with the seeds of both controllers, which are drawn from it
+// first so that serves are the same whichever controllers the simulation is built with
+fn seeded_rng(seed: u64) -> (StdRng, u64, u64) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let left_seed = rng.random();
+    let right_seed = rng.random();
+    (rng, left_seed, right_seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Difficulty, Phase, Replay, ReplayController};
+
+    // Long enough for any of these matches to finish, so a stuck simulation fails instead of hanging
+    const MAX_TICKS: u64 = 1_000_000;
+
+    fn setup(seed: u64, left_controller: ControllerKind, right_controller: ControllerKind) -> MatchSetup {
+        MatchSetup { seed, config: GameConfig::default(), left_controller, right_controller }
+    }
+
+    // Plays until the match is over and returns the final score and tick count
+    fn play(mut simulation: Simulation, mut input: impl FnMut(u64) -> TickInput) -> (u32, u32, u64) {
+        while simulation.game_state().phase() != Phase::FINISHED {
+            assert!(simulation.ticks() < MAX_TICKS, "The match did not finish");
+            simulation.step(input(simulation.ticks()));
+        }
+
+        let score = simulation.game_state().score();
+        (score.points(Side::LEFT), score.points(Side::RIGHT), simulation.ticks())
+    }
+
+    // Moves both paddles back and forth with every kind of command
+    fn scripted_input(tick: u64) -> TickInput {
+        let left_paddle = match tick / 40 % 3 {
+            0 => Some(MoveCommand::UP),
+            1 => Some(MoveCommand::DOWN),
+            _ => None
+        };
+        let right_paddle = if (tick / 60).is_multiple_of(2) {
+            Some(MoveCommand::TARGET((tick % 100) as f32 / 10.0))
+        } else {
+            Some(MoveCommand::AXIS(((tick % 21) as f32 - 10.0) / 10.0))
+        };
+
+        TickInput { left_paddle, right_paddle, serve: tick == 0, ..TickInput::default() }
+    }
+
+    #[test]
+    fn same_seed_and_inputs_give_the_same_match() {
+        let setup = setup(42, ControllerKind::HUMAN, ControllerKind::HUMAN);
+
+        let first = play(Simulation::new(setup), scripted_input);
+        let second = play(Simulation::new(setup), scripted_input);
+
+        assert_eq!(first, second);
+        assert!(first.0.max(first.1) >= GameConfig::default().rules.points_to_win);
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_ai_match() {
+        let setup = setup(7, ControllerKind::AI(Difficulty::EASY), ControllerKind::AI(Difficulty::MEDIUM));
+        let serve = |tick| TickInput { serve: tick == 0, ..TickInput::default() };
+
+        assert_eq!(play(Simulation::new(setup), serve), play(Simulation::new(setup), serve));
+    }
+
+    #[test]
+    fn replay_controllers_play_back_the_recorded_match() {
+        let setup = setup(3, ControllerKind::HUMAN, ControllerKind::HUMAN);
+        // Waits on the title screen and pauses for a while, where paddles are not updated
+        let input = |tick| TickInput {
+            serve: tick == 5,
+            toggle_pause: tick == 100 || tick == 130,
+            ..scripted_input(tick)
+        };
+
+        let mut replay = Replay::new(setup);
+        let recorded = play(Simulation::new(setup), |tick| {
+            let input = input(tick);
+            replay.record(&input);
+            input
+        });
+
+        let simulation = Simulation::with_controllers(
+            setup,
+            Box::new(ReplayController::new(replay.paddle_commands(Side::LEFT))),
+            Box::new(ReplayController::new(replay.paddle_commands(Side::RIGHT)))
+        );
+        let mut inputs = replay.inputs.into_iter();
+        let played_back = play(simulation, |_| TickInput {
+            left_paddle: None,
+            right_paddle: None,
+            ..inputs.next().unwrap()
+        });
+
+        assert_eq!(recorded, played_back);
+    }
+}